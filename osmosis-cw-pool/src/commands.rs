@@ -1,87 +1,1765 @@
+use std::str::FromStr;
+
 use cosmwasm_std::{
-    coin, to_json_binary, Addr, Coin, CosmosMsg, Decimal, DepsMut, QueryRequest, Response,
-    StdError, SubMsg, Uint128, WasmMsg, WasmQuery,
+    coin, from_json, to_json_binary, Addr, Binary, Coin, Coins, CosmosMsg, Decimal, Decimal256,
+    Deps, DepsMut, Empty, Env, MessageInfo, Order, Response, StdError, StdResult, Storage, SubMsg,
+    SubMsgResult, Timestamp, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Bound;
+use cw_utils::{one_coin, Duration};
+use osmosis_std::types::osmosis::incentives::{MsgAddToGauge, MsgCreateGauge};
+use osmosis_std::types::osmosis::lockup::LockQueryType;
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::MsgCreateDenom;
+use white_whale_std::pool_network::asset::{Asset, AssetInfo};
+use white_whale_std::pool_network::router::SwapOperation;
+use white_whale_std::pool_network::swap::MAX_ALLOWED_SLIPPAGE;
+
+use crate::contract::REPLY_ID_OFFSET;
+use crate::events::{SwapEvent, SwapType};
+use crate::helpers::{
+    asset_info_to_denom, burn_wrapped_token_msg, denom_to_asset_info, mint_wrapped_token_msg,
+    send_asset_msg,
+};
+use crate::msg::{
+    ActiveWindow, BridgeFee, CircuitBreakerConfig, Config, CreatePoolGauges, Cw20HookMsg,
+    DenomPause, ExactAmountOutAssertion, ExitPoolData, MinimumReceiveAssertion, PoolType,
+    PreSwapPrice, PriceObservation, RateLimit, RateLimitUsage, SenderAccessMode,
+    SingleSidedJoinData, SpotPriceConfig, SwapExactAmountInResponseData,
+    SwapExactAmountOutResponseData, SwapVolume, TwapRecord,
 };
-use white_whale_std::pool_network::asset::{Asset, AssetInfo, PairInfo};
+use crate::queries::{
+    assert_denoms, calc_in_amt_given_out, calc_out_amt_given_in, compute_spot_price,
+    compute_spot_prices, detect_pool_type, get_bridge_fee_share, get_max_spread, get_pool_info,
+    get_price_accumulator, get_rate_limit_window_start, get_swap_route, get_window_volume,
+    is_pool_active, is_swap_direction_paused, query_pool_info, simulate_swap, MAX_TWAP_PERIOD,
+    SECONDS_PER_DAY,
+};
+use crate::state::{
+    ACCRUED_BRIDGE_FEES, ACTIVE_WINDOW, BRIDGE_FEE, BRIDGE_FEE_EXEMPTIONS, CIRCUIT_BREAKER_CONFIG,
+    CONFIG, CW20_WRAPPED_DENOMS, DAILY_SWAP_VOLUMES, DENOM_PAUSES, IS_ACTIVE, MAX_SPREAD_OVERRIDES,
+    PENDING_OWNER, PRICE_OBSERVATIONS, RATE_LIMITS, RATE_LIMIT_USAGES, REPLY_ID_NONCE,
+    SENDER_ACCESS_LIST, SENDER_ACCESS_MODE, SENDER_RATE_LIMIT_USAGES, SLIPPAGE_CONFIG,
+    SPOT_PRICE_CONFIG, SWAP_COUNT, SWAP_ROUTES, SWAP_VOLUMES, TEMP_EXACT_OUT_ASSERTION_DATA,
+    TEMP_EXIT_POOL_DATA, TEMP_MIN_ASSERTION_DATA, TEMP_PRE_SWAP_PRICES,
+    TEMP_SINGLE_SIDED_JOIN_DATA, TWAP_RECORDS, WRAPPED_DENOM_CW20S,
+};
+use crate::ContractError;
+
+/// The maximum share of the amount out that can be charged as bridge fee
+const MAX_BRIDGE_FEE_PERCENT: u64 = 10;
+
+/// Updates the config of the contract. If the White Whale pool changes, its type is detected again.
+pub(crate) fn update_config(
+    deps: DepsMut,
+    env: Env,
+    white_whale_pool: Option<String>,
+    router: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if let Some(white_whale_pool) = white_whale_pool {
+        repoint_white_whale_pool(deps.as_ref(), &env, &mut config, &white_whale_pool)?;
+    }
+
+    if let Some(router) = router {
+        config.router = Some(deps.api.addr_validate(&router)?);
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_config".to_string()),
+        ("white_whale_pool", config.white_whale_pool.to_string()),
+        ("pool_type", config.pool_type.to_string()),
+        ("lp_token", config.lp_token.to_string()),
+        (
+            "router",
+            config
+                .router
+                .map_or("none".to_string(), |router| router.into_string()),
+        ),
+    ]))
+}
+
+/// Points the config to a new White Whale pool, e.g. when White Whale redeploys it. The new pool
+/// must hold the same assets as the current one, as the Osmosis pool denoms can't change.
+pub(crate) fn repoint_white_whale_pool(
+    deps: Deps,
+    env: &Env,
+    config: &mut Config,
+    white_whale_pool: &str,
+) -> Result<(), ContractError> {
+    let white_whale_pool = deps.api.addr_validate(white_whale_pool)?;
+    let pool_type = detect_pool_type(deps, &white_whale_pool)?;
+
+    let current_asset_infos =
+        query_pool_info(deps, env, &config.white_whale_pool, &config.pool_type)?.asset_infos;
+    let new_pool_info = query_pool_info(deps, env, &white_whale_pool, &pool_type)?;
+    let new_asset_infos = new_pool_info.asset_infos;
+
+    if current_asset_infos.len() != new_asset_infos.len()
+        || !current_asset_infos
+            .iter()
+            .all(|asset_info| new_asset_infos.contains(asset_info))
+    {
+        return Err(ContractError::PoolAssetsMismatch {
+            white_whale_pool: white_whale_pool.into_string(),
+        });
+    }
+
+    config.white_whale_pool = white_whale_pool;
+    config.pool_type = pool_type;
+    config.lp_token = new_pool_info.liquidity_token;
+
+    Ok(())
+}
+
+/// Proposes a new owner for the contract, which has to accept the ownership.
+pub(crate) fn propose_new_owner(
+    deps: DepsMut,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    PENDING_OWNER.save(deps.storage, &new_owner)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "propose_new_owner".to_string()),
+        ("new_owner", new_owner.into_string()),
+    ]))
+}
+
+/// Drops the pending ownership proposal.
+pub(crate) fn drop_ownership_proposal(deps: DepsMut) -> Result<Response, ContractError> {
+    if !PENDING_OWNER.exists(deps.storage) {
+        return Err(ContractError::NoPendingOwnershipProposal);
+    }
+
+    PENDING_OWNER.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "drop_ownership_proposal"))
+}
+
+/// Accepts the ownership of the contract, if the sender is the proposed owner.
+pub(crate) fn accept_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let pending_owner = PENDING_OWNER
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingOwnershipProposal)?;
+
+    if info.sender != pending_owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+    let previous_owner = config.owner;
+    config.owner = pending_owner;
+    CONFIG.save(deps.storage, &config)?;
+    PENDING_OWNER.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "accept_ownership".to_string()),
+        ("previous_owner", previous_owner.into_string()),
+        ("owner", config.owner.into_string()),
+    ]))
+}
+
+/// Ensures the sender is the owner of the contract.
+pub(crate) fn ensure_owner(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    if CONFIG.load(deps.storage)?.owner != *sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(())
+}
+
+/// Sets the pool to active or inactive.
+pub(crate) fn set_active(
+    deps: DepsMut,
+    env: Env,
+    is_active: bool,
+    from: Option<Timestamp>,
+    until: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    if from.is_some() || until.is_some() {
+        let ends_before_start = matches!((from, until), (Some(from), Some(until)) if from >= until);
+        let has_ended = until.is_some_and(|until| until <= env.block.time);
+        if ends_before_start || has_ended {
+            return Err(ContractError::InvalidActiveWindow);
+        }
+
+        ACTIVE_WINDOW.save(deps.storage, &ActiveWindow { from, until })?;
+    } else {
+        ACTIVE_WINDOW.remove(deps.storage);
+    }
+
+    IS_ACTIVE.save(deps.storage, &is_active)?;
+
+    let format_time =
+        |time: Option<Timestamp>| time.map_or("none".to_string(), |time| time.to_string());
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_active".to_string()),
+        ("is_active", is_active.to_string()),
+        ("from", format_time(from)),
+        ("until", format_time(until)),
+    ]))
+}
+
+/// Pauses the swaps selling or buying the denom, or resumes them.
+pub(crate) fn set_denom_pause(
+    deps: DepsMut,
+    denom: String,
+    pause: DenomPause,
+) -> Result<Response, ContractError> {
+    if pause.sell || pause.buy {
+        DENOM_PAUSES.save(deps.storage, &denom, &pause)?;
+    } else {
+        DENOM_PAUSES.remove(deps.storage, &denom);
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_denom_pause".to_string()),
+        ("denom", denom),
+        ("sell_paused", pause.sell.to_string()),
+        ("buy_paused", pause.buy.to_string()),
+    ]))
+}
+
+/// Updates the default slippage policy applied to swaps.
+pub(crate) fn update_slippage_config(
+    deps: DepsMut,
+    max_spread: Option<Decimal>,
+    use_belief_price: Option<bool>,
+) -> Result<Response, ContractError> {
+    let mut slippage_config = SLIPPAGE_CONFIG.load(deps.storage)?;
+
+    if let Some(max_spread) = max_spread {
+        validate_max_spread(max_spread)?;
+        slippage_config.max_spread = max_spread;
+    }
+
+    if let Some(use_belief_price) = use_belief_price {
+        slippage_config.use_belief_price = use_belief_price;
+    }
+
+    SLIPPAGE_CONFIG.save(deps.storage, &slippage_config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_slippage_config".to_string()),
+        ("max_spread", slippage_config.max_spread.to_string()),
+        (
+            "use_belief_price",
+            slippage_config.use_belief_price.to_string(),
+        ),
+    ]))
+}
+
+/// Updates how spot prices are computed.
+pub(crate) fn update_spot_price_config(
+    deps: DepsMut,
+    include_fees: bool,
+) -> Result<Response, ContractError> {
+    SPOT_PRICE_CONFIG.save(deps.storage, &SpotPriceConfig { include_fees })?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_spot_price_config".to_string()),
+        ("include_fees", include_fees.to_string()),
+    ]))
+}
+
+/// Sets or removes the cap on the volume of `denom` swapped out of the pool per window.
+pub(crate) fn set_rate_limit(
+    deps: DepsMut,
+    denom: String,
+    rate_limit: Option<RateLimit>,
+) -> Result<Response, ContractError> {
+    let rate_limit_attributes = match rate_limit {
+        Some(rate_limit) => {
+            let window_length = match rate_limit.window {
+                Duration::Height(blocks) => blocks,
+                Duration::Time(seconds) => seconds,
+            };
+
+            if window_length == 0
+                || rate_limit.max_volume.is_zero()
+                || rate_limit.max_sender_volume == Some(Uint128::zero())
+            {
+                return Err(ContractError::InvalidRateLimit);
+            }
+
+            RATE_LIMITS.save(deps.storage, &denom, &rate_limit)?;
+            vec![
+                ("window", rate_limit.window.to_string()),
+                ("max_volume", rate_limit.max_volume.to_string()),
+                (
+                    "max_sender_volume",
+                    rate_limit
+                        .max_sender_volume
+                        .map_or("none".to_string(), |volume| volume.to_string()),
+                ),
+            ]
+        }
+        None => {
+            RATE_LIMITS.remove(deps.storage, &denom);
+            RATE_LIMIT_USAGES.remove(deps.storage, &denom);
+            vec![("rate_limit", "none".to_string())]
+        }
+    };
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "set_rate_limit".to_string()),
+            ("denom", denom),
+        ])
+        .add_attributes(rate_limit_attributes))
+}
+
+/// Adds the amount swapped out of the pool to the volume of its rate limit, if any, refusing the
+/// swap if it exceeds the volume allowed in the current window, overall or for the sender.
+pub(crate) fn consume_rate_limit(
+    deps: DepsMut,
+    env: &Env,
+    asset_info: &AssetInfo,
+    amount: Uint128,
+    sender: &str,
+) -> Result<(), ContractError> {
+    let denom = asset_info_to_denom(deps.as_ref(), asset_info)?;
+    let rate_limit = match RATE_LIMITS.may_load(deps.storage, &denom)? {
+        Some(rate_limit) => rate_limit,
+        None => return Ok(()),
+    };
+
+    let window_start = get_rate_limit_window_start(&rate_limit.window, &env.block);
+
+    let volume = get_window_volume(
+        RATE_LIMIT_USAGES.may_load(deps.storage, &denom)?,
+        window_start,
+    )
+    .checked_add(amount)?;
+    if volume > rate_limit.max_volume {
+        return Err(ContractError::RateLimitExceeded {
+            denom,
+            volume,
+            max_volume: rate_limit.max_volume,
+        });
+    }
+    RATE_LIMIT_USAGES.save(
+        deps.storage,
+        &denom,
+        &RateLimitUsage {
+            window_start,
+            volume,
+        },
+    )?;
+
+    if let Some(max_sender_volume) = rate_limit.max_sender_volume {
+        let key = (denom.as_str(), sender);
+        let sender_volume = get_window_volume(
+            SENDER_RATE_LIMIT_USAGES.may_load(deps.storage, key)?,
+            window_start,
+        )
+        .checked_add(amount)?;
+        if sender_volume > max_sender_volume {
+            return Err(ContractError::SenderRateLimitExceeded {
+                sender: sender.to_string(),
+                denom,
+                volume: sender_volume,
+                max_volume: max_sender_volume,
+            });
+        }
+        SENDER_RATE_LIMIT_USAGES.save(
+            deps.storage,
+            key,
+            &RateLimitUsage {
+                window_start,
+                volume: sender_volume,
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Sets or removes the circuit breaker refusing the swaps that move the spot price too much.
+pub(crate) fn update_circuit_breaker_config(
+    deps: DepsMut,
+    config: Option<CircuitBreakerConfig>,
+) -> Result<Response, ContractError> {
+    let attributes = match config {
+        Some(config) => {
+            if config.max_swap_price_change.is_zero() || config.max_block_price_change.is_zero() {
+                return Err(ContractError::InvalidCircuitBreakerConfig);
+            }
+
+            CIRCUIT_BREAKER_CONFIG.save(deps.storage, &config)?;
+            vec![
+                (
+                    "max_swap_price_change",
+                    config.max_swap_price_change.to_string(),
+                ),
+                (
+                    "max_block_price_change",
+                    config.max_block_price_change.to_string(),
+                ),
+            ]
+        }
+        None => {
+            CIRCUIT_BREAKER_CONFIG.remove(deps.storage);
+            vec![("circuit_breaker", "none".to_string())]
+        }
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "update_circuit_breaker_config")
+        .add_attributes(attributes))
+}
+
+/// Observes the spot price of the pair swapped after a swap, refusing the swap if it moved the
+/// price beyond the circuit breaker bands and updating the TWAP records of the pair.
+pub(crate) fn observe_swap_price(
+    mut deps: DepsMut,
+    env: &Env,
+    reply_id: u64,
+) -> Result<(), ContractError> {
+    let pre_swap_price = match TEMP_PRE_SWAP_PRICES.may_load(deps.storage, reply_id)? {
+        Some(pre_swap_price) => pre_swap_price,
+        None => return Ok(()),
+    };
+    TEMP_PRE_SWAP_PRICES.remove(deps.storage, reply_id);
+
+    let base_denom = pre_swap_price.base_denom.as_str();
+    let quote_denom = pre_swap_price.quote_denom.as_str();
+
+    // the TWAP can be queried in both directions, and the average of the inverse prices isn't the
+    // inverse of the average price
+    let (spot_price, inverse_spot_price) =
+        compute_spot_prices(deps.as_ref(), env, quote_denom, base_denom)?;
+    check_circuit_breaker(deps.branch(), env, &pre_swap_price, spot_price)?;
+
+    update_twap_record(deps.branch(), env, base_denom, quote_denom, spot_price)?;
+    update_twap_record(deps, env, quote_denom, base_denom, inverse_spot_price)?;
+
+    Ok(())
+}
+
+/// Checks how much a swap moved the spot price of the pair swapped, both on its own and along with
+/// the previous swaps of the block, refusing it if it's beyond the circuit breaker bands.
+fn check_circuit_breaker(
+    deps: DepsMut,
+    env: &Env,
+    pre_swap_price: &PreSwapPrice,
+    spot_price: Decimal256,
+) -> Result<(), ContractError> {
+    let config = match CIRCUIT_BREAKER_CONFIG.may_load(deps.storage)? {
+        Some(config) => config,
+        None => return Ok(()),
+    };
+
+    assert_price_change(
+        pre_swap_price.spot_price,
+        spot_price,
+        config.max_swap_price_change,
+    )?;
+
+    let key = (
+        pre_swap_price.base_denom.as_str(),
+        pre_swap_price.quote_denom.as_str(),
+    );
+    let block_start_price = match PRICE_OBSERVATIONS.may_load(deps.storage, key)? {
+        Some(observation) if observation.block_height == env.block.height => {
+            observation.block_start_price
+        }
+        _ => pre_swap_price.spot_price,
+    };
+    assert_price_change(block_start_price, spot_price, config.max_block_price_change)?;
+
+    PRICE_OBSERVATIONS.save(
+        deps.storage,
+        key,
+        &PriceObservation {
+            block_height: env.block.height,
+            block_start_price,
+            last_price: spot_price,
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Records the spot price of the pair from now on, accumulating the one it had since its last
+/// record, and prunes the records no longer needed to compute the TWAPs that can be queried.
+fn update_twap_record(
+    deps: DepsMut,
+    env: &Env,
+    base_denom: &str,
+    quote_denom: &str,
+    spot_price: Decimal256,
+) -> StdResult<()> {
+    let now = env.block.time.seconds();
+    let price_accumulator =
+        get_price_accumulator(deps.as_ref(), base_denom, quote_denom, now)?.unwrap_or_default();
+
+    TWAP_RECORDS.save(
+        deps.storage,
+        (base_denom, quote_denom, now),
+        &TwapRecord {
+            spot_price,
+            price_accumulator,
+        },
+    )?;
+
+    // the last record taken before the oldest queryable time is kept to extrapolate from it
+    let prune_before = now.saturating_sub(MAX_TWAP_PERIOD);
+    let mut stale_times = TWAP_RECORDS
+        .prefix((base_denom, quote_denom))
+        .keys(
+            deps.storage,
+            None,
+            Some(Bound::exclusive(prune_before)),
+            Order::Ascending,
+        )
+        .collect::<StdResult<Vec<u64>>>()?;
+    stale_times.pop();
+
+    for time in stale_times {
+        TWAP_RECORDS.remove(deps.storage, (base_denom, quote_denom, time));
+    }
+
+    Ok(())
+}
+
+/// Asserts the spot price didn't change more than allowed relative to the reference price.
+fn assert_price_change(
+    reference_price: Decimal256,
+    spot_price: Decimal256,
+    max_price_change: Decimal,
+) -> Result<(), ContractError> {
+    let price_change = spot_price
+        .abs_diff(reference_price)
+        .checked_div(reference_price)?;
+
+    if price_change > Decimal256::from(max_price_change) {
+        return Err(ContractError::CircuitBreakerTripped {
+            price_change,
+            max_price_change,
+        });
+    }
+
+    Ok(())
+}
+
+/// Gets the spot price of the pair before a swap, so it can be told how much the swap moves it.
+fn get_pre_swap_price(
+    deps: Deps,
+    env: &Env,
+    offer_denom: &str,
+    ask_denom: &str,
+) -> StdResult<PreSwapPrice> {
+    // prices are observed in a single direction per pair, whichever way the swap goes
+    let (base_denom, quote_denom) = if offer_denom < ask_denom {
+        (offer_denom, ask_denom)
+    } else {
+        (ask_denom, offer_denom)
+    };
+
+    Ok(PreSwapPrice {
+        base_denom: base_denom.to_string(),
+        quote_denom: quote_denom.to_string(),
+        spot_price: compute_spot_price(deps, env, quote_denom, base_denom)?,
+    })
+}
+
+/// Sets or removes the max spread override for swaps going from `offer_denom` to `ask_denom`.
+pub(crate) fn set_max_spread_override(
+    deps: DepsMut,
+    offer_denom: String,
+    ask_denom: String,
+    max_spread: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let max_spread_attribute = match max_spread {
+        Some(max_spread) => {
+            validate_max_spread(max_spread)?;
+            assert_denoms(deps.as_ref(), offer_denom.clone(), ask_denom.clone())?;
+
+            MAX_SPREAD_OVERRIDES.save(
+                deps.storage,
+                (offer_denom.as_str(), ask_denom.as_str()),
+                &max_spread,
+            )?;
+            max_spread.to_string()
+        }
+        None => {
+            MAX_SPREAD_OVERRIDES.remove(deps.storage, (offer_denom.as_str(), ask_denom.as_str()));
+            "none".to_string()
+        }
+    };
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_max_spread_override".to_string()),
+        ("offer_denom", offer_denom),
+        ("ask_denom", ask_denom),
+        ("max_spread", max_spread_attribute),
+    ]))
+}
+
+/// Sets the bridge fee charged on swaps. A zero share removes it.
+pub(crate) fn set_bridge_fee(
+    deps: DepsMut,
+    share: Decimal,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let max_share = Decimal::percent(MAX_BRIDGE_FEE_PERCENT);
+    if share > max_share {
+        return Err(ContractError::InvalidBridgeFee { share, max_share });
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    if share.is_zero() {
+        BRIDGE_FEE.remove(deps.storage);
+    } else {
+        BRIDGE_FEE.save(
+            deps.storage,
+            &BridgeFee {
+                share,
+                recipient: recipient.clone(),
+            },
+        )?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_bridge_fee".to_string()),
+        ("share", share.to_string()),
+        ("recipient", recipient.into_string()),
+    ]))
+}
+
+/// Exempts the given sender from the bridge fee, or removes its exemption.
+pub(crate) fn set_bridge_fee_exemption(
+    deps: DepsMut,
+    sender: String,
+    exempt: bool,
+) -> Result<Response, ContractError> {
+    let sender = deps.api.addr_validate(&sender)?;
+
+    if exempt {
+        BRIDGE_FEE_EXEMPTIONS.save(deps.storage, &sender, &Empty {})?;
+    } else {
+        BRIDGE_FEE_EXEMPTIONS.remove(deps.storage, &sender);
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_bridge_fee_exemption".to_string()),
+        ("sender", sender.into_string()),
+        ("exempt", exempt.to_string()),
+    ]))
+}
+
+/// Sets how the sender access list is applied to swaps, or stops applying it.
+pub(crate) fn set_sender_access_mode(
+    deps: DepsMut,
+    mode: Option<SenderAccessMode>,
+) -> Result<Response, ContractError> {
+    let mode_attribute = match mode {
+        Some(mode) => {
+            SENDER_ACCESS_MODE.save(deps.storage, &mode)?;
+            match mode {
+                SenderAccessMode::Allowlist => "allowlist",
+                SenderAccessMode::Denylist => "denylist",
+            }
+        }
+        None => {
+            SENDER_ACCESS_MODE.remove(deps.storage);
+            "none"
+        }
+    };
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_sender_access_mode".to_string()),
+        ("mode", mode_attribute.to_string()),
+    ]))
+}
+
+/// Adds senders to and removes senders from the sender access list.
+pub(crate) fn update_sender_access_list(
+    deps: DepsMut,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    for sender in &add {
+        let sender = deps.api.addr_validate(sender)?;
+        SENDER_ACCESS_LIST.save(deps.storage, &sender, &Empty {})?;
+    }
+
+    for sender in &remove {
+        let sender = deps.api.addr_validate(sender)?;
+        SENDER_ACCESS_LIST.remove(deps.storage, &sender);
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_sender_access_list".to_string()),
+        ("added", add.join(",")),
+        ("removed", remove.join(",")),
+    ]))
+}
+
+/// Ensures the sender is allowed to swap by the sender access list, if applied.
+fn ensure_sender_allowed(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    let is_allowed = match SENDER_ACCESS_MODE.may_load(deps.storage)? {
+        Some(SenderAccessMode::Allowlist) => SENDER_ACCESS_LIST.has(deps.storage, sender),
+        Some(SenderAccessMode::Denylist) => !SENDER_ACCESS_LIST.has(deps.storage, sender),
+        None => true,
+    };
+
+    if !is_allowed {
+        return Err(ContractError::SenderNotAllowed {
+            sender: sender.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Deducts the bridge fee from the swapped tokens held by the contract, accruing it and creating
+/// the message sending it to the bridge fee recipient. Returns the amount left for the sender.
+pub(crate) fn collect_bridge_fee(
+    deps: DepsMut,
+    env: &Env,
+    asset_info: &AssetInfo,
+    swap_amount: Uint128,
+    bridge_fee_share: Decimal,
+) -> Result<(Uint128, Option<CosmosMsg>), ContractError> {
+    let bridge_fee_amount = swap_amount * bridge_fee_share;
+
+    let bridge_fee = match BRIDGE_FEE.may_load(deps.storage)? {
+        Some(bridge_fee) if !bridge_fee_amount.is_zero() => bridge_fee,
+        _ => return Ok((swap_amount, None)),
+    };
+
+    let denom = asset_info_to_denom(deps.as_ref(), asset_info)?;
+    ACCRUED_BRIDGE_FEES.update(deps.storage, &denom, |accrued| -> StdResult<Uint128> {
+        Ok(accrued.unwrap_or_default().checked_add(bridge_fee_amount)?)
+    })?;
+
+    let fee_msg = send_asset_msg(
+        deps.as_ref(),
+        env,
+        Asset {
+            info: asset_info.clone(),
+            amount: bridge_fee_amount,
+        },
+        bridge_fee.recipient.into_string(),
+    )?;
+
+    Ok((swap_amount.checked_sub(bridge_fee_amount)?, Some(fee_msg)))
+}
+
+/// Sets or removes the route swaps going from `offer_denom` to `ask_denom` are executed through.
+pub(crate) fn set_swap_route(
+    deps: DepsMut,
+    offer_denom: String,
+    ask_denom: String,
+    operations: Option<Vec<SwapOperation>>,
+) -> Result<Response, ContractError> {
+    let operations_attribute = match operations {
+        Some(operations) => {
+            if CONFIG.load(deps.storage)?.router.is_none() {
+                return Err(ContractError::RouterNotSet);
+            }
+            validate_swap_route(deps.as_ref(), &offer_denom, &ask_denom, &operations)?;
+
+            SWAP_ROUTES.save(
+                deps.storage,
+                (offer_denom.as_str(), ask_denom.as_str()),
+                &operations,
+            )?;
+            operations.len().to_string()
+        }
+        None => {
+            SWAP_ROUTES.remove(deps.storage, (offer_denom.as_str(), ask_denom.as_str()));
+            "none".to_string()
+        }
+    };
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_swap_route".to_string()),
+        ("offer_denom", offer_denom),
+        ("ask_denom", ask_denom),
+        ("operations", operations_attribute),
+    ]))
+}
+
+/// Validates the swap operations go from the offer asset to the ask asset, each one starting with
+/// the asset the previous one ends with.
+fn validate_swap_route(
+    deps: Deps,
+    offer_denom: &str,
+    ask_denom: &str,
+    operations: &[SwapOperation],
+) -> Result<(), ContractError> {
+    let offer_asset_info = denom_to_asset_info(deps, offer_denom)?;
+    let ask_asset_info = denom_to_asset_info(deps, ask_denom)?;
+
+    let mut current_asset_info = offer_asset_info.clone();
+    for operation in operations {
+        let SwapOperation::TerraSwap {
+            offer_asset_info: operation_offer_asset_info,
+            ask_asset_info: operation_ask_asset_info,
+        } = operation;
+
+        if *operation_offer_asset_info != current_asset_info {
+            return Err(ContractError::InvalidSwapRoute {
+                offer_denom: offer_denom.to_string(),
+                ask_denom: ask_denom.to_string(),
+            });
+        }
+        current_asset_info = operation_ask_asset_info.clone();
+    }
+
+    if operations.is_empty()
+        || offer_asset_info == ask_asset_info
+        || current_asset_info != ask_asset_info
+    {
+        return Err(ContractError::InvalidSwapRoute {
+            offer_denom: offer_denom.to_string(),
+            ask_denom: ask_denom.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// The denom the incentives module charges its gauge fees in
+const GAUGE_FEE_DENOM: &str = "uosmo";
+/// The fee charged by the incentives module to create a gauge
+const CREATE_GAUGE_FEE: u128 = 50_000_000;
+/// The fee charged by the incentives module to add rewards to a gauge
+const ADD_TO_GAUGE_FEE: u128 = 25_000_000;
+
+/// Creates the given incentive gauges, owned by the contract.
+pub(crate) fn create_gauges(
+    env: Env,
+    info: MessageInfo,
+    msgs: Vec<MsgCreateGauge>,
+) -> Result<Response, ContractError> {
+    validate_gauges(&msgs, true)?;
+
+    let mut gauge_coins = vec![];
+    for msg in &msgs {
+        for coin in &msg.coins {
+            gauge_coins.push(Coin {
+                denom: coin.denom.clone(),
+                amount: Uint128::from_str(&coin.amount)?,
+            });
+        }
+        gauge_coins.push(coin(CREATE_GAUGE_FEE, GAUGE_FEE_DENOM));
+    }
+    ensure_gauge_funds(&info.funds, gauge_coins)?;
+
+    let gauges = msgs.len();
+    let msgs = msgs
+        .into_iter()
+        .map(|msg| MsgCreateGauge {
+            owner: env.contract.address.to_string(),
+            ..msg
+        })
+        .collect::<Vec<MsgCreateGauge>>();
+
+    Ok(Response::default().add_messages(msgs).add_attributes(vec![
+        ("action", "create_gauges".to_string()),
+        ("gauges", gauges.to_string()),
+    ]))
+}
+
+/// Adds the given rewards to the incentive gauge owned by the contract.
+pub(crate) fn add_to_gauge(
+    env: Env,
+    info: MessageInfo,
+    gauge_id: u64,
+    rewards: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    if rewards.is_empty() || rewards.iter().any(|reward| reward.amount.is_zero()) {
+        return Err(ContractError::InvalidGauge {
+            reason: "the rewards must be positive".to_string(),
+        });
+    }
+
+    let mut gauge_coins = rewards.clone();
+    gauge_coins.push(coin(ADD_TO_GAUGE_FEE, GAUGE_FEE_DENOM));
+    ensure_gauge_funds(&info.funds, gauge_coins)?;
+
+    let rewards_attribute = rewards
+        .iter()
+        .map(Coin::to_string)
+        .collect::<Vec<String>>()
+        .join(",");
+
+    Ok(Response::default()
+        .add_message(MsgAddToGauge {
+            owner: env.contract.address.to_string(),
+            gauge_id,
+            rewards: rewards.into_iter().map(Into::into).collect(),
+        })
+        .add_attributes(vec![
+            ("action", "add_to_gauge".to_string()),
+            ("gauge_id", gauge_id.to_string()),
+            ("rewards", rewards_attribute),
+        ]))
+}
+
+/// Ensures the funds sent pay exactly for the gauge coins and the fees of the incentives module, so
+/// the gauges are never paid for out of the contract's balance, which holds the swapped tokens in
+/// flight.
+fn ensure_gauge_funds(funds: &[Coin], gauge_coins: Vec<Coin>) -> Result<(), ContractError> {
+    let mut expected = Coins::default();
+    for coin in gauge_coins {
+        expected.add(coin)?;
+    }
+
+    let mut sent = Coins::default();
+    for coin in funds {
+        sent.add(coin.clone())?;
+    }
+
+    if sent != expected {
+        return Err(ContractError::InvalidGauge {
+            reason: format!(
+                "the funds sent [{}] must match the gauge coins and fees [{}]",
+                sent, expected
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+/// Validates the gauges to create after the pool is created. The gauges of the default lockable
+/// durations aren't supported, as the LP shares of the pool are the White Whale pool's LP tokens
+/// rather than the ones the incentives module locks.
+pub(crate) fn validate_create_pool_gauges(
+    create_pool_gauges: &CreatePoolGauges,
+) -> Result<(), ContractError> {
+    match create_pool_gauges {
+        CreatePoolGauges::DefaultLockableDurations {} => Err(ContractError::InvalidGauge {
+            reason: "the default lockable durations gauges aren't supported, use custom gauges \
+                instead"
+                .to_string(),
+        }),
+        // the pool id isn't known until the pool is created
+        CreatePoolGauges::Custom { msgs } => validate_gauges(msgs, false),
+    }
+}
+
+/// Validates the incentive gauges to create. Their owner isn't validated, as it's set by whoever
+/// creates them. Gauges without lock must have a pool id, unless it isn't known yet.
+fn validate_gauges(msgs: &[MsgCreateGauge], pool_id_known: bool) -> Result<(), ContractError> {
+    if msgs.is_empty() {
+        return Err(ContractError::InvalidGauge {
+            reason: "no gauges to create".to_string(),
+        });
+    }
+
+    for msg in msgs {
+        validate_gauge(msg, pool_id_known)
+            .map_err(|reason| ContractError::InvalidGauge { reason })?;
+    }
+
+    Ok(())
+}
+
+/// Validates an incentive gauge to create, returning the reason it's invalid, if any.
+fn validate_gauge(msg: &MsgCreateGauge, pool_id_known: bool) -> Result<(), String> {
+    if msg.coins.is_empty() {
+        return Err("the gauge coins can't be empty".to_string());
+    }
+
+    for coin in &msg.coins {
+        let amount = Uint128::from_str(&coin.amount).map_err(|err| err.to_string())?;
+        if coin.denom.is_empty() || amount.is_zero() {
+            return Err(format!("invalid gauge coin {}{}", coin.amount, coin.denom));
+        }
+    }
+
+    // same as the incentives module, perpetual gauges distribute their coins every epoch
+    if msg.is_perpetual && msg.num_epochs_paid_over != 1 {
+        return Err("perpetual gauges must be paid over a single epoch".to_string());
+    }
+    if msg.num_epochs_paid_over == 0 {
+        return Err("the gauge must be paid over at least one epoch".to_string());
+    }
+
+    let distribute_to = msg
+        .distribute_to
+        .as_ref()
+        .ok_or_else(|| "the gauge must have a distribution condition".to_string())?;
+
+    match distribute_to.lock_query_type {
+        lock_query_type if lock_query_type == LockQueryType::ByDuration as i32 => {
+            if distribute_to.denom.is_empty() {
+                return Err("the gauge must have a distribution denom".to_string());
+            }
+
+            let duration = distribute_to
+                .duration
+                .as_ref()
+                .ok_or_else(|| "gauges by duration must have a lock duration".to_string())?;
+            let is_zero = duration.seconds == 0 && duration.nanos == 0;
+            if duration.seconds < 0 || duration.nanos < 0 || is_zero {
+                return Err("the gauge lock duration must be positive".to_string());
+            }
+        }
+        lock_query_type if lock_query_type == LockQueryType::ByTime as i32 => {
+            if distribute_to.denom.is_empty() {
+                return Err("the gauge must have a distribution denom".to_string());
+            }
 
-use crate::contract::ASSERT_MINIMUM_RECEIVE_REPLY_ID;
-use crate::msg::{
-    MinimumReceiveAssertion, SwapExactAmountInResponseData, SwapExactAmountOutResponseData,
-};
-use crate::queries::{calc_in_amt_given_out, calc_out_amt_given_in};
-use crate::state::{CONFIG, IS_ACTIVE, TEMP_MIN_ASSERTION_DATA};
-use crate::ContractError;
+            if distribute_to.timestamp.is_none() {
+                return Err("gauges by time must have a lock timestamp".to_string());
+            }
+        }
+        lock_query_type if lock_query_type == LockQueryType::NoLock as i32 => {
+            if pool_id_known && msg.pool_id == 0 {
+                return Err("gauges without lock must have a pool id".to_string());
+            }
+        }
+        lock_query_type => {
+            return Err(format!(
+                "unsupported gauge lock query type {}",
+                lock_query_type
+            ));
+        }
+    }
 
-/// Sets the pool to active or inactive.
-pub(crate) fn set_active(deps: DepsMut, is_active: bool) -> Result<Response, ContractError> {
-    IS_ACTIVE.save(deps.storage, &is_active)?;
-    Ok(Response::new().add_attribute("action", "set_active"))
+    Ok(())
 }
 
-/// Swaps an exact amount of tokens in for as many tokens out as possible.
-pub(crate) fn swap_exact_amount_in(
+/// Registers the tokenfactory denom wrapping a CW20 token of the pool.
+pub(crate) fn register_cw20(
+    deps: DepsMut,
+    env: Env,
+    contract_addr: String,
+    subdenom: String,
+) -> Result<Response, ContractError> {
+    let contract_addr = deps.api.addr_validate(&contract_addr)?.into_string();
+
+    if CW20_WRAPPED_DENOMS.has(deps.storage, &contract_addr) {
+        return Err(ContractError::Cw20AlreadyRegistered { contract_addr });
+    }
+
+    // make sure the token is in the pool
+    let pool_info = get_pool_info(deps.as_ref(), &env)?;
+    let asset_info = AssetInfo::Token {
+        contract_addr: contract_addr.clone(),
+    };
+    if !pool_info.asset_infos.contains(&asset_info) {
+        return Err(StdError::generic_err(format!(
+            "Asset {} not found in the pool",
+            contract_addr
+        ))
+        .into());
+    }
+
+    let denom = format!("factory/{}/{}", env.contract.address, subdenom);
+
+    CW20_WRAPPED_DENOMS.save(deps.storage, &contract_addr, &denom)?;
+    WRAPPED_DENOM_CW20S.save(deps.storage, &denom, &contract_addr)?;
+
+    Ok(Response::default()
+        .add_message(MsgCreateDenom {
+            sender: env.contract.address.into_string(),
+            subdenom,
+        })
+        .add_attributes(vec![
+            ("action", "register_cw20".to_string()),
+            ("contract_addr", contract_addr),
+            ("denom", denom),
+        ]))
+}
+
+/// Receives CW20 tokens sent to the contract.
+pub(crate) fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // the sender is the CW20 token contract
+    let contract_addr = info.sender.into_string();
+
+    match from_json(&cw20_msg.msg)? {
+        Cw20HookMsg::Wrap { receiver } => {
+            if !CW20_WRAPPED_DENOMS.has(deps.storage, &contract_addr) {
+                return Err(ContractError::UnregisteredCw20 { contract_addr });
+            }
+
+            let asset_info = AssetInfo::Token { contract_addr };
+            let receiver = deps
+                .api
+                .addr_validate(&receiver.unwrap_or(cw20_msg.sender))?
+                .into_string();
+
+            let mint_msg = mint_wrapped_token_msg(
+                deps.as_ref(),
+                &env,
+                &asset_info,
+                cw20_msg.amount,
+                receiver.clone(),
+            )?;
+
+            Ok(Response::default()
+                .add_message(mint_msg)
+                .add_attributes(vec![
+                    ("action", "wrap".to_string()),
+                    ("asset", asset_info.to_string()),
+                    ("amount", cw20_msg.amount.to_string()),
+                    ("receiver", receiver),
+                ]))
+        }
+        Cw20HookMsg::ExitPool { receiver } => {
+            let lp_token = Asset {
+                info: AssetInfo::Token { contract_addr },
+                amount: cw20_msg.amount,
+            };
+            let receiver = deps
+                .api
+                .addr_validate(&receiver.unwrap_or(cw20_msg.sender))?
+                .into_string();
+
+            withdraw_liquidity(deps, env, lp_token, receiver)
+        }
+    }
+}
+
+/// Unwraps the tokenfactory denom sent into the CW20 token it wraps.
+pub(crate) fn unwrap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let wrapped_token = one_coin(&info)?;
+
+    let contract_addr = WRAPPED_DENOM_CW20S
+        .may_load(deps.storage, &wrapped_token.denom)?
+        .ok_or_else(|| ContractError::UnregisteredWrappedDenom {
+            denom: wrapped_token.denom.clone(),
+        })?;
+
+    let receiver = deps
+        .api
+        .addr_validate(&receiver.unwrap_or(info.sender.into_string()))?
+        .into_string();
+
+    Ok(Response::default()
+        .add_message(burn_wrapped_token_msg(&env, wrapped_token.clone()))
+        .add_message(WasmMsg::Execute {
+            contract_addr: contract_addr.clone(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: receiver.clone(),
+                amount: wrapped_token.amount,
+            })?,
+            funds: vec![],
+        })
+        .add_attributes(vec![
+            ("action", "unwrap".to_string()),
+            ("asset", contract_addr),
+            ("amount", wrapped_token.amount.to_string()),
+            ("receiver", receiver),
+        ]))
+}
+
+/// Provides the tokens sent as liquidity to the White Whale pool. If a single asset is sent to a
+/// pair, half of it is swapped for the other asset first and the liquidity is provided once the
+/// swap is done.
+pub(crate) fn join_pool(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    slippage_tolerance: Option<Decimal>,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let sender = info.sender;
+    let receiver = deps
+        .api
+        .addr_validate(&receiver.unwrap_or(sender.to_string()))?
+        .into_string();
+
+    let config = CONFIG.load(deps.storage)?;
+    let pool_info = get_pool_info(deps.as_ref(), &env)?;
+    let assets = info
+        .funds
+        .iter()
+        .map(|coin| {
+            Ok(Asset {
+                info: denom_to_asset_info(deps.as_ref(), &coin.denom)?,
+                amount: coin.amount,
+            })
+        })
+        .collect::<StdResult<Vec<Asset>>>()?;
+
+    if assets.is_empty()
+        || assets
+            .iter()
+            .any(|asset| asset.amount.is_zero() || !pool_info.asset_infos.contains(&asset.info))
+    {
+        return Err(ContractError::InvalidJoinPoolTokens);
+    }
+
+    // the wrapped CW20 tokens sent are burned, as the contract provides the CW20 tokens it holds
+    let burn_msgs = info
+        .funds
+        .iter()
+        .zip(assets.iter())
+        .filter(|(_, asset)| matches!(asset.info, AssetInfo::Token { .. }))
+        .map(|(coin, _)| burn_wrapped_token_msg(&env, coin.clone()))
+        .collect::<Vec<CosmosMsg>>();
+
+    if assets.len() == pool_info.asset_infos.len() {
+        ensure_is_active(&deps, &env, None)?;
+
+        return Ok(Response::default()
+            .add_messages(burn_msgs)
+            .add_messages(provide_liquidity_msgs(
+                &config,
+                &pool_info.asset_infos,
+                assets,
+                slippage_tolerance,
+                receiver.clone(),
+            )?)
+            .add_attributes(vec![
+                ("action", "join_pool".to_string()),
+                ("receiver", receiver),
+            ]));
+    }
+
+    if assets.len() != 1 || config.pool_type != PoolType::Pair {
+        return Err(ContractError::InvalidJoinPoolTokens);
+    }
+
+    // single sided join, swap half of the tokens for the other asset of the pair. The swap is
+    // subject to the swap direction pauses and the sender access list like any other swap.
+    let offer = info.funds[0].clone();
+    let offer_asset_info = assets[0].info.clone();
+    let ask_asset_info = pool_info
+        .asset_infos
+        .into_iter()
+        .find(|asset_info| *asset_info != offer_asset_info)
+        .ok_or(ContractError::PairedAssetMissmatch)?;
+    let ask_denom = asset_info_to_denom(deps.as_ref(), &ask_asset_info)?;
+    ensure_is_active(&deps, &env, Some((&offer.denom, &ask_denom)))?;
+    ensure_sender_allowed(deps.as_ref(), &sender)?;
+
+    let swap_amount = offer.amount.multiply_ratio(1u128, 2u128);
+    if swap_amount.is_zero() {
+        return Err(ContractError::ZeroAmount);
+    }
+    let swap_offer = coin(swap_amount.u128(), offer.denom.clone());
+
+    let prev_balance =
+        ask_asset_info.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+
+    let reply_id = next_reply_id(deps.storage)?;
+    TEMP_SINGLE_SIDED_JOIN_DATA.save(
+        deps.storage,
+        reply_id,
+        &SingleSidedJoinData {
+            offer_asset: Asset {
+                info: offer_asset_info.clone(),
+                amount: offer.amount.checked_sub(swap_amount)?,
+            },
+            ask_asset_info: ask_asset_info.clone(),
+            prev_balance,
+            slippage_tolerance,
+            sender: sender.into_string(),
+            receiver: receiver.clone(),
+        },
+    )?;
+
+    // the swap moves the price of the pair like any other swap
+    let pre_swap_price = get_pre_swap_price(deps.as_ref(), &env, &offer.denom, &ask_denom)?;
+    TEMP_PRE_SWAP_PRICES.save(deps.storage, reply_id, &pre_swap_price)?;
+
+    let (belief_price, max_spread) = get_swap_params(&deps, &swap_offer, &ask_denom)?;
+    record_swap_in(deps.branch(), &env, &swap_offer)?;
+
+    // the swap messages burn the wrapped tokens swapped, so only the rest is burned here
+    let mut response = Response::default();
+    if let AssetInfo::Token { .. } = offer_asset_info {
+        response = response.add_message(burn_wrapped_token_msg(
+            &env,
+            coin(offer.amount.checked_sub(swap_amount)?.u128(), offer.denom),
+        ));
+    }
+
+    Ok(response
+        .add_submessages(create_swap_msgs(
+            &deps,
+            &env,
+            swap_offer,
+            ask_asset_info,
+            belief_price,
+            max_spread,
+            env.contract.address.to_string(),
+            reply_id,
+        )?)
+        .add_attributes(vec![
+            ("action", "join_pool".to_string()),
+            ("receiver", receiver),
+            ("swap_amount", swap_amount.to_string()),
+        ]))
+}
+
+/// Provides the liquidity of a single sided join once half of the tokens were swapped for the
+/// other asset of the pair. The swap is held to the circuit breaker and rate limits like any other.
+pub(crate) fn provide_single_sided_liquidity(
+    mut deps: DepsMut,
+    env: Env,
+    reply_id: u64,
+    result: &SubMsgResult,
+) -> Result<Response, ContractError> {
+    let SingleSidedJoinData {
+        offer_asset,
+        ask_asset_info,
+        prev_balance,
+        slippage_tolerance,
+        sender,
+        receiver,
+    } = TEMP_SINGLE_SIDED_JOIN_DATA
+        .may_load(deps.storage, reply_id)?
+        .ok_or(ContractError::CannotReadAssertionData {})?;
+
+    let balance =
+        ask_asset_info.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+    let ask_asset = Asset {
+        info: ask_asset_info,
+        amount: balance.checked_sub(prev_balance)?,
+    };
+
+    observe_swap_price(deps.branch(), &env, reply_id)?;
+    consume_rate_limit(
+        deps.branch(),
+        &env,
+        &ask_asset.info,
+        ask_asset.amount,
+        &sender,
+    )?;
+
+    TEMP_SINGLE_SIDED_JOIN_DATA.remove(deps.storage, reply_id);
+
+    record_swap_out(
+        deps.branch(),
+        &env,
+        &ask_asset.info,
+        ask_asset.amount,
+        Uint128::zero(),
+    )?;
+    record_pool_fees(deps.branch(), &env, result)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let pool_info = get_pool_info(deps.as_ref(), &env)?;
+
+    Ok(Response::default()
+        .add_messages(provide_liquidity_msgs(
+            &config,
+            &pool_info.asset_infos,
+            vec![offer_asset, ask_asset.clone()],
+            slippage_tolerance,
+            receiver,
+        )?)
+        .add_attributes(vec![
+            ("action", "provide_single_sided_liquidity".to_string()),
+            (
+                "swapped_asset",
+                format!("{}{}", ask_asset.amount, ask_asset.info),
+            ),
+        ]))
+}
+
+/// Creates the messages providing the given assets, held by the contract, as liquidity to the
+/// White Whale pool. The pool is allowed to spend the CW20 tokens provided.
+fn provide_liquidity_msgs(
+    config: &Config,
+    asset_infos: &[AssetInfo],
+    assets: Vec<Asset>,
+    slippage_tolerance: Option<Decimal>,
+    receiver: String,
+) -> StdResult<Vec<CosmosMsg>> {
+    let pool = config.white_whale_pool.to_string();
+
+    // the assets are provided in the same order as in the pool
+    let assets = asset_infos
+        .iter()
+        .map(|asset_info| {
+            assets
+                .iter()
+                .find(|asset| asset.info == *asset_info)
+                .cloned()
+                .ok_or_else(|| StdError::generic_err(format!("Asset {} not provided", asset_info)))
+        })
+        .collect::<StdResult<Vec<Asset>>>()?;
+
+    let mut msgs = vec![];
+    let mut funds = vec![];
+    for asset in &assets {
+        match &asset.info {
+            AssetInfo::NativeToken { denom } => funds.push(coin(asset.amount.u128(), denom)),
+            AssetInfo::Token { contract_addr } => msgs.push(
+                WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    msg: to_json_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                        spender: pool.clone(),
+                        amount: asset.amount,
+                        expires: None,
+                    })?,
+                    funds: vec![],
+                }
+                .into(),
+            ),
+        }
+    }
+    funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+    let msg = match config.pool_type {
+        PoolType::Pair => to_json_binary(
+            &white_whale_std::pool_network::pair::ExecuteMsg::ProvideLiquidity {
+                assets: assets
+                    .try_into()
+                    .map_err(|_| StdError::generic_err("A pair holds two assets"))?,
+                slippage_tolerance,
+                receiver: Some(receiver),
+            },
+        )?,
+        PoolType::Trio => to_json_binary(
+            &white_whale_std::pool_network::trio::ExecuteMsg::ProvideLiquidity {
+                assets: assets
+                    .try_into()
+                    .map_err(|_| StdError::generic_err("A trio holds three assets"))?,
+                slippage_tolerance,
+                receiver: Some(receiver),
+            },
+        )?,
+    };
+
+    msgs.push(
+        WasmMsg::Execute {
+            contract_addr: pool,
+            msg,
+            funds,
+        }
+        .into(),
+    );
+
+    Ok(msgs)
+}
+
+/// Withdraws the liquidity of the LP shares sent along with the message from the White Whale pool.
+pub(crate) fn exit_pool(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let lp_coin = one_coin(&info)?;
+    let receiver = deps
+        .api
+        .addr_validate(&receiver.unwrap_or(info.sender.into_string()))?
+        .into_string();
+
+    withdraw_liquidity(
+        deps,
+        env,
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: lp_coin.denom,
+            },
+            amount: lp_coin.amount,
+        },
+        receiver,
+    )
+}
+
+/// Withdraws the liquidity of the given LP shares from the White Whale pool. The withdrawn assets
+/// are sent to the contract, which forwards them to the receiver once the withdrawal is done.
+fn withdraw_liquidity(
+    deps: DepsMut,
+    env: Env,
+    lp_token: Asset,
+    receiver: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if lp_token.info != config.lp_token {
+        return Err(ContractError::InvalidLpToken);
+    }
+    if lp_token.amount.is_zero() {
+        return Err(ContractError::ZeroAmount);
+    }
+
+    let prev_balances = get_pool_info(deps.as_ref(), &env)?
+        .asset_infos
+        .into_iter()
+        .map(|asset_info| {
+            let amount =
+                asset_info.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+            Ok(Asset {
+                info: asset_info,
+                amount,
+            })
+        })
+        .collect::<StdResult<Vec<Asset>>>()?;
+
+    let reply_id = next_reply_id(deps.storage)?;
+    TEMP_EXIT_POOL_DATA.save(
+        deps.storage,
+        reply_id,
+        &ExitPoolData {
+            prev_balances,
+            receiver: receiver.clone(),
+        },
+    )?;
+
+    let pool = config.white_whale_pool.into_string();
+    let withdraw_msg = match lp_token.info {
+        AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+            contract_addr: pool,
+            msg: match config.pool_type {
+                PoolType::Pair => to_json_binary(
+                    &white_whale_std::pool_network::pair::ExecuteMsg::WithdrawLiquidity {},
+                )?,
+                PoolType::Trio => to_json_binary(
+                    &white_whale_std::pool_network::trio::ExecuteMsg::WithdrawLiquidity {},
+                )?,
+            },
+            funds: vec![coin(lp_token.amount.u128(), denom)],
+        },
+        AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+            contract_addr,
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: pool,
+                amount: lp_token.amount,
+                msg: match config.pool_type {
+                    PoolType::Pair => to_json_binary(
+                        &white_whale_std::pool_network::pair::Cw20HookMsg::WithdrawLiquidity {},
+                    )?,
+                    PoolType::Trio => to_json_binary(
+                        &white_whale_std::pool_network::trio::Cw20HookMsg::WithdrawLiquidity {},
+                    )?,
+                },
+            })?,
+            funds: vec![],
+        },
+    };
+
+    Ok(Response::default()
+        .add_submessage(SubMsg::reply_on_success(withdraw_msg, reply_id))
+        .add_attributes(vec![
+            ("action", "exit_pool".to_string()),
+            ("shares_in", lp_token.amount.to_string()),
+            ("receiver", receiver),
+        ]))
+}
+
+/// Forwards the assets withdrawn from the White Whale pool to the receiver.
+pub(crate) fn forward_withdrawn_liquidity(
     deps: DepsMut,
+    env: Env,
+    reply_id: u64,
+) -> Result<Response, ContractError> {
+    let ExitPoolData {
+        prev_balances,
+        receiver,
+    } = TEMP_EXIT_POOL_DATA
+        .may_load(deps.storage, reply_id)?
+        .ok_or(ContractError::CannotReadAssertionData {})?;
+
+    let mut msgs = vec![];
+    let mut withdrawn_assets = vec![];
+    for prev_balance in prev_balances {
+        let balance =
+            prev_balance
+                .info
+                .query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+        let withdrawn_asset = Asset {
+            info: prev_balance.info,
+            amount: balance.checked_sub(prev_balance.amount)?,
+        };
+
+        if withdrawn_asset.amount.is_zero() {
+            continue;
+        }
+
+        withdrawn_assets.push(format!(
+            "{}{}",
+            withdrawn_asset.amount, withdrawn_asset.info
+        ));
+        msgs.push(send_asset_msg(
+            deps.as_ref(),
+            &env,
+            withdrawn_asset,
+            receiver.clone(),
+        )?);
+    }
+
+    TEMP_EXIT_POOL_DATA.remove(deps.storage, reply_id);
+
+    Ok(Response::default().add_messages(msgs).add_attributes(vec![
+        ("action", "forward_withdrawn_liquidity".to_string()),
+        ("withdrawn_assets", withdrawn_assets.join(",")),
+        ("receiver", receiver),
+    ]))
+}
+
+/// Swaps an exact amount of tokens in for as many tokens out as possible. If there's a route for
+/// the swap, it's executed by the White Whale router instead of the pool.
+pub(crate) fn swap_exact_amount_in(
+    mut deps: DepsMut,
+    env: Env,
     sender: String,
     token_in: Coin,
     token_out_denom: String,
     minimum_receive: Uint128,
+    swap_fee: Decimal,
 ) -> Result<Response, ContractError> {
-    ensure_is_active(&deps)?;
+    ensure_is_active(&deps, &env, Some((&token_in.denom, &token_out_denom)))?;
 
-    let config = CONFIG.load(deps.storage)?;
     let sender = deps.api.addr_validate(sender.as_str())?;
+    ensure_sender_allowed(deps.as_ref(), &sender)?;
 
-    // get the pool info
-    let pool = config.white_whale_pool;
-    let pair_info = get_pair_info(&deps, &pool)?;
-    let ask_asset_info = get_paired_asset_info(&token_in, pair_info, &token_out_denom)?;
+    let route = get_swap_route(deps.as_ref(), &token_in.denom, &token_out_denom)?;
+    let ask_asset_info = match route {
+        Some(_) => denom_to_asset_info(deps.as_ref(), &token_out_denom)?,
+        None => {
+            // get the pool info
+            let pool_info = get_pool_info(deps.as_ref(), &env)?;
+            get_paired_asset_info(
+                &deps,
+                &token_in.denom,
+                &pool_info.asset_infos,
+                &token_out_denom,
+            )?
+        }
+    };
 
-    let expected_token_out =
-        calc_out_amt_given_in(deps.as_ref(), token_in.clone(), token_out_denom)?.token_out;
+    let expected_token_out = calc_out_amt_given_in(
+        deps.as_ref(),
+        token_in.clone(),
+        token_out_denom.clone(),
+        swap_fee,
+        Some(&sender),
+    )?
+    .token_out;
 
-    // let receiver_balance = ask_asset_info.query_balance(&deps.querier, deps.api, sender.clone())?;
-    let receiver_balance = ask_asset_info.query_pool(&deps.querier, deps.api, sender.clone())?;
+    // the contract receives the swapped tokens and forwards them to the sender once the swap is
+    // assessed
+    let receiver = env.contract.address.clone();
+    let receiver_balance = ask_asset_info.query_pool(&deps.querier, deps.api, receiver.clone())?;
 
     let assertion_data = MinimumReceiveAssertion {
-        asset_info: ask_asset_info,
+        asset_info: ask_asset_info.clone(),
         prev_balance: receiver_balance,
         minimum_receive,
-        receiver: sender.clone().into_string(),
+        bridge_fee_share: get_bridge_fee_share(deps.as_ref(), Some(&sender))?,
+        recipient: sender.to_string(),
+    };
+
+    let reply_id = next_reply_id(deps.storage)?;
+    TEMP_MIN_ASSERTION_DATA.save(deps.storage, reply_id, &assertion_data)?;
+
+    // routed swaps go through other pools, so only the prices of the direct ones are observed
+    if route.is_none() {
+        let pre_swap_price =
+            get_pre_swap_price(deps.as_ref(), &env, &token_in.denom, &token_out_denom)?;
+        TEMP_PRE_SWAP_PRICES.save(deps.storage, reply_id, &pre_swap_price)?;
+    }
+
+    record_swap_in(deps.branch(), &env, &token_in)?;
+
+    let swap_event = SwapEvent {
+        swap_type: SwapType::ExactAmountIn,
+        reply_id,
+        sender: sender.into_string(),
+        token_in: token_in.clone(),
+        token_in_max_amount: token_in.amount,
+        token_out: expected_token_out.clone(),
+        token_out_min_amount: minimum_receive,
+        swap_fee,
+        routed: route.is_some(),
     };
 
-    TEMP_MIN_ASSERTION_DATA.save(deps.storage, &assertion_data)?;
+    let swap_msgs = match route {
+        Some(operations) => {
+            let max_spread = get_max_spread(deps.as_ref(), &token_in.denom, &token_out_denom)?;
+            create_route_swap_msgs(
+                &deps,
+                &env,
+                token_in,
+                operations,
+                max_spread,
+                receiver.into_string(),
+                reply_id,
+            )?
+        }
+        None => {
+            let (belief_price, max_spread) = get_swap_params(&deps, &token_in, &token_out_denom)?;
+            create_swap_msgs(
+                &deps,
+                &env,
+                token_in,
+                ask_asset_info,
+                belief_price,
+                max_spread,
+                receiver.into_string(),
+                reply_id,
+            )?
+        }
+    };
 
     Ok(Response::default()
         .set_data(to_json_binary(&SwapExactAmountInResponseData {
             token_out_amount: expected_token_out.amount,
         })?)
-        .add_submessage(SubMsg::reply_on_success(
-            create_swap_msg(pool.into_string(), token_in, sender.into_string())?,
-            ASSERT_MINIMUM_RECEIVE_REPLY_ID,
-        ))
-        .add_attributes(vec![("action", "swap_exact_amount_in".to_string())]))
+        .add_submessages(swap_msgs)
+        .add_attributes(vec![("action", "swap_exact_amount_in".to_string())])
+        .add_event(swap_event.into()))
 }
 
 /// Swaps as many tokens in as possible for an exact amount of tokens out.
 pub(crate) fn swap_exact_amount_out(
-    deps: DepsMut,
+    mut deps: DepsMut,
+    env: Env,
     sender: String,
     token_out: Coin,
     token_in_max_amount: Uint128,
     token_in_denom: String,
+    swap_fee: Decimal,
 ) -> Result<Response, ContractError> {
-    ensure_is_active(&deps)?;
+    ensure_is_active(&deps, &env, Some((&token_in_denom, &token_out.denom)))?;
+
+    if get_swap_route(deps.as_ref(), &token_in_denom, &token_out.denom)?.is_some() {
+        return Err(ContractError::RoutedSwapExactAmountOut);
+    }
 
-    let config = CONFIG.load(deps.storage)?;
     let sender = deps.api.addr_validate(sender.as_str())?;
+    ensure_sender_allowed(deps.as_ref(), &sender)?;
 
     // get the pool info
-    let pool = config.white_whale_pool;
-    let pair_info = get_pair_info(&deps, &pool)?;
-    get_paired_asset_info(&token_out, pair_info, &token_in_denom)?;
+    let pool_info = get_pool_info(deps.as_ref(), &env)?;
+    let ask_asset_info = get_paired_asset_info(
+        &deps,
+        &token_in_denom,
+        &pool_info.asset_infos,
+        &token_out.denom,
+    )?;
 
-    let expected_token_in =
-        calc_in_amt_given_out(deps.as_ref(), token_out.clone(), token_in_denom.clone())?.token_in;
+    let expected_token_in = calc_in_amt_given_out(
+        deps.as_ref(),
+        token_out.clone(),
+        token_in_denom.clone(),
+        swap_fee,
+        Some(&sender),
+    )?
+    .token_in;
 
     // assert the expected token in amount to get the desired token out amount is less than the
     // maximum token in allowed
@@ -92,104 +1770,460 @@ pub(crate) fn swap_exact_amount_out(
         });
     }
 
+    // the contract receives the swapped tokens and forwards them to the sender once the swap is
+    // assessed
+    let receiver = env.contract.address.clone();
+    let receiver_balance = ask_asset_info.query_pool(&deps.querier, deps.api, receiver.clone())?;
+
+    let assertion_data = ExactAmountOutAssertion {
+        asset_info: ask_asset_info.clone(),
+        prev_balance: receiver_balance,
+        token_out_amount: token_out.amount,
+        bridge_fee_share: get_bridge_fee_share(deps.as_ref(), Some(&sender))?,
+        recipient: sender.to_string(),
+    };
+
+    let reply_id = next_reply_id(deps.storage)?;
+    TEMP_EXACT_OUT_ASSERTION_DATA.save(deps.storage, reply_id, &assertion_data)?;
+
+    let pre_swap_price =
+        get_pre_swap_price(deps.as_ref(), &env, &token_in_denom, &token_out.denom)?;
+    TEMP_PRE_SWAP_PRICES.save(deps.storage, reply_id, &pre_swap_price)?;
+
+    // the excess token in, i.e. token_in_max_amount - token_in_amount, is refunded to the sender
+    // by the cosmwasmpool module after the swap
+    let offer = coin(expected_token_in.amount.u128(), token_in_denom);
+    record_swap_in(deps.branch(), &env, &offer)?;
+
+    let swap_event = SwapEvent {
+        swap_type: SwapType::ExactAmountOut,
+        reply_id,
+        sender: sender.into_string(),
+        token_in: offer.clone(),
+        token_in_max_amount,
+        token_out: token_out.clone(),
+        token_out_min_amount: token_out.amount,
+        swap_fee,
+        routed: false,
+    };
+
+    let (belief_price, max_spread) = get_swap_params(&deps, &offer, &token_out.denom)?;
+
     Ok(Response::default()
         .set_data(to_json_binary(&SwapExactAmountOutResponseData {
             token_in_amount: expected_token_in.amount,
         })?)
-        .add_message(create_swap_msg(
-            pool.into_string(),
-            coin(expected_token_in.amount.u128(), token_in_denom.clone()),
-            sender.into_string(),
+        .add_submessages(create_swap_msgs(
+            &deps,
+            &env,
+            offer,
+            ask_asset_info,
+            belief_price,
+            max_spread,
+            receiver.into_string(),
+            reply_id,
         )?)
-        .add_attributes(vec![("action", "swap_exact_amount_out".to_string())]))
+        .add_attributes(vec![("action", "swap_exact_amount_out".to_string())])
+        .add_event(swap_event.into()))
 }
 
-/// Creates a swap message for the White Whale pool.
-fn create_swap_msg(
-    contract_addr: String,
+/// Counts the swap and adds the amount swapped into the pool to the volume of its denom. The swap
+/// of a single sided join is counted as well.
+fn record_swap_in(deps: DepsMut, env: &Env, token_in: &Coin) -> StdResult<()> {
+    let swap_count = SWAP_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    SWAP_COUNT.save(deps.storage, &(swap_count + 1))?;
+
+    add_swap_volume(
+        deps.storage,
+        env,
+        &token_in.denom,
+        &SwapVolume {
+            volume_in: token_in.amount,
+            ..SwapVolume::default()
+        },
+    )
+}
+
+/// Adds the fees charged by the White Whale pool on the swaps of a reply to the fees of the denoms
+/// swapped out. The fees are read from the swap events of the pool, so they're the ones actually
+/// charged, including on the hops of routed swaps going through the pool.
+pub(crate) fn record_pool_fees(deps: DepsMut, env: &Env, result: &SubMsgResult) -> StdResult<()> {
+    let events = match result {
+        SubMsgResult::Ok(response) => &response.events,
+        SubMsgResult::Err(_) => return Ok(()),
+    };
+    let pool = CONFIG.load(deps.storage)?.white_whale_pool;
+
+    for event in events.iter().filter(|event| event.ty == "wasm") {
+        let attribute = |key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|attribute| attribute.key == key)
+                .map(|attribute| attribute.value.as_str())
+        };
+
+        if attribute("_contract_address") != Some(pool.as_str())
+            || attribute("action") != Some("swap")
+        {
+            continue;
+        }
+
+        // the ask asset of the pool is either a native denom or a CW20 token wrapped by a denom
+        let ask_asset = attribute("ask_asset")
+            .ok_or_else(|| StdError::generic_err("The pool swap event has no ask asset"))?;
+        let denom = CW20_WRAPPED_DENOMS
+            .may_load(deps.storage, ask_asset)?
+            .unwrap_or_else(|| ask_asset.to_string());
+
+        let fees = [
+            "swap_fee_amount",
+            "protocol_fee_amount",
+            "burn_fee_amount",
+            "osmosis_fee_amount",
+        ]
+        .into_iter()
+        .filter_map(attribute)
+        .try_fold(Uint128::zero(), |fees, amount| -> StdResult<Uint128> {
+            Ok(fees.checked_add(Uint128::from_str(amount)?)?)
+        })?;
+
+        add_swap_volume(
+            deps.storage,
+            env,
+            &denom,
+            &SwapVolume {
+                fees,
+                ..SwapVolume::default()
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Adds the amount swapped out of the pool, and the bridge fee charged on it, to the volume of its
+/// denom.
+pub(crate) fn record_swap_out(
+    deps: DepsMut,
+    env: &Env,
+    asset_info: &AssetInfo,
+    amount: Uint128,
+    bridge_fee_amount: Uint128,
+) -> StdResult<()> {
+    let denom = asset_info_to_denom(deps.as_ref(), asset_info)?;
+
+    add_swap_volume(
+        deps.storage,
+        env,
+        &denom,
+        &SwapVolume {
+            volume_out: amount,
+            fees: bridge_fee_amount,
+            ..SwapVolume::default()
+        },
+    )
+}
+
+/// Adds the given volume to the all time and daily volumes of the denom.
+fn add_swap_volume(
+    storage: &mut dyn Storage,
+    env: &Env,
+    denom: &str,
+    volume: &SwapVolume,
+) -> StdResult<()> {
+    let add_volume = |total: Option<SwapVolume>| -> StdResult<SwapVolume> {
+        let total = total.unwrap_or_default();
+        Ok(SwapVolume {
+            volume_in: total.volume_in.checked_add(volume.volume_in)?,
+            volume_out: total.volume_out.checked_add(volume.volume_out)?,
+            fees: total.fees.checked_add(volume.fees)?,
+        })
+    };
+
+    let day = env.block.time.seconds() / SECONDS_PER_DAY;
+    SWAP_VOLUMES.update(storage, denom, add_volume)?;
+    DAILY_SWAP_VOLUMES.update(storage, (denom, day), add_volume)?;
+
+    Ok(())
+}
+
+/// Gets a unique reply id for a swap, join or exit. The nonce is never reset, so the temp data of
+/// one can't be mistaken for the one of another, even if it was left behind.
+fn next_reply_id(storage: &mut dyn Storage) -> Result<u64, ContractError> {
+    let nonce = REPLY_ID_NONCE.may_load(storage)?.unwrap_or_default();
+    REPLY_ID_NONCE.save(
+        storage,
+        &nonce
+            .checked_add(1)
+            .ok_or_else(|| StdError::generic_err("The reply id nonce overflowed"))?,
+    )?;
+
+    Ok(REPLY_ID_OFFSET + nonce)
+}
+
+/// Gets the belief price and max spread to send to the White Whale pool when swapping `offer`
+/// for `ask_denom`, as per the slippage policy.
+fn get_swap_params(
+    deps: &DepsMut,
+    offer: &Coin,
+    ask_denom: &str,
+) -> Result<(Option<Decimal>, Decimal), ContractError> {
+    let max_spread = get_max_spread(deps.as_ref(), &offer.denom, ask_denom)?;
+
+    if !SLIPPAGE_CONFIG.load(deps.storage)?.use_belief_price {
+        return Ok((None, max_spread));
+    }
+
+    // the belief price is the price of the ask asset in terms of the offer asset before the spread
+    // is applied, so the pool checks the spread of the actual swap against it
+    let simulation = simulate_swap(deps.as_ref(), offer.clone(), ask_denom)?;
+    let belief_price = Decimal::checked_from_ratio(
+        offer.amount,
+        simulation
+            .return_amount
+            .checked_add(simulation.spread_amount)?,
+    )?;
+
+    Ok((Some(belief_price), max_spread))
+}
+
+/// Validates the max spread doesn't exceed the maximum allowed by the White Whale pool.
+pub(crate) fn validate_max_spread(max_spread: Decimal) -> Result<(), ContractError> {
+    let max_allowed_spread = Decimal::from_str(MAX_ALLOWED_SLIPPAGE)?;
+
+    if max_spread > max_allowed_spread {
+        return Err(ContractError::InvalidMaxSpread {
+            max_spread,
+            max_allowed_spread,
+        });
+    }
+
+    Ok(())
+}
+
+/// Creates the messages to swap the offer tokens for the ask asset on the White Whale pool,
+/// replying with the given reply id once the swap is done. Wrapped CW20 tokens are burned and the
+/// CW20 tokens held by the contract are sent to the pool instead.
+#[allow(clippy::too_many_arguments)]
+fn create_swap_msgs(
+    deps: &DepsMut,
+    env: &Env,
     coin: Coin,
-    sender: String,
-) -> Result<CosmosMsg, ContractError> {
-    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr,
-        msg: to_json_binary(&white_whale_std::pool_network::pair::ExecuteMsg::Swap {
-            offer_asset: Asset {
-                info: AssetInfo::NativeToken {
-                    denom: coin.clone().denom,
+    ask_asset_info: AssetInfo,
+    belief_price: Option<Decimal>,
+    max_spread: Decimal,
+    receiver: String,
+    reply_id: u64,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let pool = config.white_whale_pool.into_string();
+
+    match denom_to_asset_info(deps.as_ref(), &coin.denom)? {
+        AssetInfo::NativeToken { denom } => Ok(vec![SubMsg::reply_on_success(
+            WasmMsg::Execute {
+                contract_addr: pool,
+                msg: swap_msg(
+                    &config.pool_type,
+                    Asset {
+                        info: AssetInfo::NativeToken { denom },
+                        amount: coin.amount,
+                    },
+                    ask_asset_info,
+                    belief_price,
+                    max_spread,
+                    receiver,
+                )?,
+                funds: vec![coin],
+            },
+            reply_id,
+        )]),
+        AssetInfo::Token {
+            contract_addr: token_addr,
+        } => Ok(vec![
+            SubMsg::new(burn_wrapped_token_msg(env, coin.clone())),
+            SubMsg::reply_on_success(
+                WasmMsg::Execute {
+                    contract_addr: token_addr,
+                    msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                        contract: pool,
+                        amount: coin.amount,
+                        msg: cw20_swap_hook_msg(
+                            &config.pool_type,
+                            ask_asset_info,
+                            belief_price,
+                            max_spread,
+                            receiver,
+                        )?,
+                    })?,
+                    funds: vec![],
                 },
-                amount: coin.clone().amount,
+                reply_id,
+            ),
+        ]),
+    }
+}
+
+/// Creates the messages to swap the offer tokens through the given swap operations on the White
+/// Whale router, asserting the minimum receive amount once the swap is done. Wrapped CW20 tokens
+/// are burned and the CW20 tokens held by the contract are sent to the router instead.
+fn create_route_swap_msgs(
+    deps: &DepsMut,
+    env: &Env,
+    coin: Coin,
+    operations: Vec<SwapOperation>,
+    max_spread: Decimal,
+    receiver: String,
+    reply_id: u64,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let router = CONFIG
+        .load(deps.storage)?
+        .router
+        .ok_or(ContractError::RouterNotSet)?
+        .into_string();
+
+    match denom_to_asset_info(deps.as_ref(), &coin.denom)? {
+        AssetInfo::NativeToken { .. } => Ok(vec![SubMsg::reply_on_success(
+            WasmMsg::Execute {
+                contract_addr: router,
+                msg: to_json_binary(
+                    &white_whale_std::pool_network::router::ExecuteMsg::ExecuteSwapOperations {
+                        operations,
+                        minimum_receive: None,
+                        to: Some(receiver),
+                        max_spread: Some(max_spread),
+                    },
+                )?,
+                funds: vec![coin],
             },
-            belief_price: None,
-            max_spread: Some(Decimal::percent(30)),
-            to: Some(sender),
-        })?,
-        funds: vec![coin],
-    }))
+            reply_id,
+        )]),
+        AssetInfo::Token {
+            contract_addr: token_addr,
+        } => {
+            let hook_msg =
+                white_whale_std::pool_network::router::Cw20HookMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive: None,
+                    to: Some(receiver),
+                    max_spread: Some(max_spread),
+                };
+
+            Ok(vec![
+                SubMsg::new(burn_wrapped_token_msg(env, coin.clone())),
+                SubMsg::reply_on_success(
+                    WasmMsg::Execute {
+                        contract_addr: token_addr,
+                        msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                            contract: router,
+                            amount: coin.amount,
+                            msg: to_json_binary(&hook_msg)?,
+                        })?,
+                        funds: vec![],
+                    },
+                    reply_id,
+                ),
+            ])
+        }
+    }
+}
+
+/// Creates the White Whale pool message swapping the native offer asset for the ask asset. Trios
+/// need the ask asset to be specified, while pairs swap for the other asset of the pair.
+fn swap_msg(
+    pool_type: &PoolType,
+    offer_asset: Asset,
+    ask_asset_info: AssetInfo,
+    belief_price: Option<Decimal>,
+    max_spread: Decimal,
+    receiver: String,
+) -> StdResult<Binary> {
+    match pool_type {
+        PoolType::Pair => to_json_binary(&white_whale_std::pool_network::pair::ExecuteMsg::Swap {
+            offer_asset,
+            belief_price,
+            max_spread: Some(max_spread),
+            to: Some(receiver),
+        }),
+        PoolType::Trio => to_json_binary(&white_whale_std::pool_network::trio::ExecuteMsg::Swap {
+            offer_asset,
+            ask_asset: ask_asset_info,
+            belief_price,
+            max_spread: Some(max_spread),
+            to: Some(receiver),
+        }),
+    }
 }
 
-/// Gets the pair info from the White Whale pool.
-fn get_pair_info(deps: &DepsMut, pool: &Addr) -> Result<PairInfo, ContractError> {
-    let pair_info: PairInfo = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: pool.to_string(),
-        msg: to_json_binary(&white_whale_std::pool_network::pair::QueryMsg::Pair {})?,
-    }))?;
-    Ok(pair_info)
+/// Creates the White Whale pool CW20 hook message swapping the CW20 tokens sent for the ask asset
+fn cw20_swap_hook_msg(
+    pool_type: &PoolType,
+    ask_asset_info: AssetInfo,
+    belief_price: Option<Decimal>,
+    max_spread: Decimal,
+    receiver: String,
+) -> StdResult<Binary> {
+    match pool_type {
+        PoolType::Pair => to_json_binary(&white_whale_std::pool_network::pair::Cw20HookMsg::Swap {
+            belief_price,
+            max_spread: Some(max_spread),
+            to: Some(receiver),
+        }),
+        PoolType::Trio => to_json_binary(&white_whale_std::pool_network::trio::Cw20HookMsg::Swap {
+            ask_asset: ask_asset_info,
+            belief_price,
+            max_spread: Some(max_spread),
+            to: Some(receiver),
+        }),
+    }
 }
 
-/// Gets the asset a token is paired with in the White Whale pool.
+/// Gets the asset info of the ask token in the White Whale pool, making sure both the offer and
+/// ask tokens are in the pool and they are different assets.
 fn get_paired_asset_info(
-    token_a: &Coin,
-    pair_info: PairInfo,
-    token_b_denom: &String,
+    deps: &DepsMut,
+    offer_denom: &str,
+    asset_infos: &[AssetInfo],
+    ask_denom: &str,
 ) -> Result<AssetInfo, ContractError> {
+    let offer_asset_info = denom_to_asset_info(deps.as_ref(), offer_denom)?;
+
     // sanity check to make sure the input token is in the pool
-    if !pair_info.asset_infos.clone().into_iter().any(|asset_info| {
-        asset_info
-            == AssetInfo::NativeToken {
-                denom: token_a.clone().denom,
-            }
-    }) {
-        return Err(StdError::generic_err(format!(
-            "Asset {} not found in the pool",
-            token_a.denom
-        ))
-        .into());
+    if !asset_infos.contains(&offer_asset_info) {
+        return Err(
+            StdError::generic_err(format!("Asset {} not found in the pool", offer_denom)).into(),
+        );
     }
 
-    let asset_info: AssetInfo = pair_info
-        .asset_infos
-        .into_iter()
-        .find(|asset_info| {
-            *asset_info
-                != AssetInfo::NativeToken {
-                    denom: token_a.clone().denom,
-                }
-        })
-        .ok_or_else(|| {
-            StdError::generic_err(format!(
-                "The asset paired with {} was not found",
-                token_a.denom
-            ))
-        })?;
-
-    // verify the token found matches the expected one
-    match asset_info.clone() {
-        AssetInfo::Token { .. } => return Err(StdError::generic_err("Token not supported").into()),
-        AssetInfo::NativeToken { denom } => {
-            if denom != *token_b_denom {
-                return Err(ContractError::PairedAssetMissmatch);
-            }
-        }
+    // the ask asset is picked by denom, as trios have more than one asset the offer asset can be
+    // swapped for
+    let ask_asset_info = denom_to_asset_info(deps.as_ref(), ask_denom)?;
+    if ask_asset_info == offer_asset_info || !asset_infos.contains(&ask_asset_info) {
+        return Err(ContractError::PairedAssetMissmatch);
     }
 
-    Ok(asset_info)
+    Ok(ask_asset_info)
 }
 
-/// Asserts that the pool is active.
-fn ensure_is_active(deps: &DepsMut) -> Result<(), ContractError> {
-    let is_active = IS_ACTIVE.load(deps.storage)?;
-    if !is_active {
+/// Ensures the pool is active at the current block time and, for swaps, that swapping the token
+/// in for the token out isn't paused.
+fn ensure_is_active(
+    deps: &DepsMut,
+    env: &Env,
+    swap_denoms: Option<(&str, &str)>,
+) -> Result<(), ContractError> {
+    if !is_pool_active(deps.as_ref(), env.block.time)? {
         return Err(ContractError::InactivePool);
     }
+
+    if let Some((token_in_denom, token_out_denom)) = swap_denoms {
+        if is_swap_direction_paused(deps.as_ref(), token_in_denom, token_out_denom)? {
+            return Err(ContractError::SwapDirectionPaused {
+                token_in_denom: token_in_denom.to_string(),
+                token_out_denom: token_out_denom.to_string(),
+            });
+        }
+    }
+
     Ok(())
 }
@@ -2,41 +2,74 @@ use cosmwasm_std::{
     entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
     StdError, StdResult,
 };
-use cw2::{get_contract_version, set_contract_version};
+use cw2::set_contract_version;
 use semver::Version;
-use white_whale_std::migrate_guards::check_contract_name;
+use white_whale_std::pool_network::asset::Asset;
 
 use crate::error::ContractError;
-use crate::msg::{Config, InstantiateMsg, MigrateMsg, MinimumReceiveAssertion, QueryMsg, SudoMsg};
-use crate::state::{CONFIG, IS_ACTIVE, TEMP_MIN_ASSERTION_DATA};
-use crate::ContractError::MigrateInvalidVersion;
-use crate::{commands, queries};
+use crate::events::{SwapSettlementEvent, SwapType};
+use crate::helpers::{asset_info_to_denom, send_asset_msg};
+use crate::msg::{
+    Config, ExactAmountOutAssertion, ExecuteMsg, InstantiateMsg, MigrateMsg,
+    MinimumReceiveAssertion, QueryMsg, SudoMsg,
+};
+use crate::state::{
+    CONFIG, IS_ACTIVE, PENDING_OWNER, SLIPPAGE_CONFIG, SPOT_PRICE_CONFIG,
+    TEMP_EXACT_OUT_ASSERTION_DATA, TEMP_EXIT_POOL_DATA, TEMP_MIN_ASSERTION_DATA,
+    TEMP_SINGLE_SIDED_JOIN_DATA,
+};
+use crate::{commands, migrations, queries};
 
-const CONTRACT_NAME: &str = "crates.io:white_whale-osmosis_cw_pool";
-const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
-pub(crate) const ASSERT_MINIMUM_RECEIVE_REPLY_ID: u64 = 1;
+pub(crate) const CONTRACT_NAME: &str = "crates.io:white_whale-osmosis_cw_pool";
+pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Swaps, joins and exits get unique reply ids starting from this one, so their temp data can be
+/// keyed by it
+pub(crate) const REPLY_ID_OFFSET: u64 = 1_000;
 
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
-    _info: MessageInfo,
+    env: Env,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    let owner = match msg.owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => info.sender,
+    };
+    let white_whale_pool = deps.api.addr_validate(&msg.white_whale_pool)?;
+    let pool_type = queries::detect_pool_type(deps.as_ref(), &white_whale_pool)?;
+    let lp_token = queries::query_pool_info(deps.as_ref(), &env, &white_whale_pool, &pool_type)?
+        .liquidity_token;
+
     CONFIG.save(
         deps.storage,
         &Config {
-            white_whale_pool: deps.api.addr_validate(&msg.white_whale_pool)?,
+            owner,
+            white_whale_pool,
+            pool_type,
+            lp_token,
+            router: None,
         },
     )?;
 
     IS_ACTIVE.save(deps.storage, &true)?;
 
+    let slippage_config = msg.slippage_config.unwrap_or_default();
+    commands::validate_max_spread(slippage_config.max_spread)?;
+    SLIPPAGE_CONFIG.save(deps.storage, &slippage_config)?;
+
+    SPOT_PRICE_CONFIG.save(deps.storage, &msg.spot_price_config.unwrap_or_default())?;
+
     let response = Response::default().add_attributes(vec![("action", "instantiate".to_string())]);
 
     if let Some(after_pool_created) = msg.after_pool_created {
+        if let Some(create_pool_gauges) = &after_pool_created.create_pool_guages {
+            commands::validate_create_pool_gauges(create_pool_gauges)?;
+        }
+
         Ok(response.set_data(to_json_binary(&after_pool_created)?))
     } else {
         Ok(response)
@@ -44,25 +77,147 @@ pub fn instantiate(
 }
 
 #[entry_point]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(cw20_msg) => commands::receive_cw20(deps, env, info, cw20_msg),
+        ExecuteMsg::Unwrap { receiver } => commands::unwrap(deps, env, info, receiver),
+        ExecuteMsg::UpdateConfig {
+            white_whale_pool,
+            router,
+        } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::update_config(deps, env, white_whale_pool, router)
+        }
+        ExecuteMsg::SetActive {
+            is_active,
+            from,
+            until,
+        } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::set_active(deps, env, is_active, from, until)
+        }
+        ExecuteMsg::SetDenomPause { denom, pause } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::set_denom_pause(deps, denom, pause)
+        }
+        ExecuteMsg::UpdateSlippageConfig {
+            max_spread,
+            use_belief_price,
+        } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::update_slippage_config(deps, max_spread, use_belief_price)
+        }
+        ExecuteMsg::SetMaxSpreadOverride {
+            offer_denom,
+            ask_denom,
+            max_spread,
+        } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::set_max_spread_override(deps, offer_denom, ask_denom, max_spread)
+        }
+        ExecuteMsg::UpdateSpotPriceConfig { include_fees } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::update_spot_price_config(deps, include_fees)
+        }
+        ExecuteMsg::SetSwapRoute {
+            offer_denom,
+            ask_denom,
+            operations,
+        } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::set_swap_route(deps, offer_denom, ask_denom, operations)
+        }
+        ExecuteMsg::SetBridgeFee { share, recipient } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::set_bridge_fee(deps, share, recipient)
+        }
+        ExecuteMsg::SetBridgeFeeExemption { sender, exempt } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::set_bridge_fee_exemption(deps, sender, exempt)
+        }
+        ExecuteMsg::SetSenderAccessMode { mode } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::set_sender_access_mode(deps, mode)
+        }
+        ExecuteMsg::UpdateSenderAccessList { add, remove } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::update_sender_access_list(deps, add, remove)
+        }
+        ExecuteMsg::SetRateLimit { denom, rate_limit } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::set_rate_limit(deps, denom, rate_limit)
+        }
+        ExecuteMsg::UpdateCircuitBreakerConfig { config } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::update_circuit_breaker_config(deps, config)
+        }
+        ExecuteMsg::CreateGauges { msgs } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::create_gauges(env, info, msgs)
+        }
+        ExecuteMsg::AddToGauge { gauge_id, rewards } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::add_to_gauge(env, info, gauge_id, rewards)
+        }
+        ExecuteMsg::ProposeNewOwner { new_owner } => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::propose_new_owner(deps, new_owner)
+        }
+        ExecuteMsg::DropOwnershipProposal {} => {
+            commands::ensure_owner(deps.as_ref(), &info.sender)?;
+            commands::drop_ownership_proposal(deps)
+        }
+        ExecuteMsg::AcceptOwnership {} => commands::accept_ownership(deps, info),
+        ExecuteMsg::JoinPool {
+            slippage_tolerance,
+            receiver,
+        } => commands::join_pool(deps, env, info, slippage_tolerance, receiver),
+        ExecuteMsg::ExitPool { receiver } => commands::exit_pool(deps, env, info, receiver),
+    }
+}
+
+#[entry_point]
+pub fn reply(mut deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg.id {
-        ASSERT_MINIMUM_RECEIVE_REPLY_ID => {
+        id if TEMP_MIN_ASSERTION_DATA.has(deps.storage, id) => {
             let MinimumReceiveAssertion {
                 asset_info,
                 prev_balance,
                 minimum_receive,
-                receiver,
+                recipient,
+                bridge_fee_share,
             }: MinimumReceiveAssertion = TEMP_MIN_ASSERTION_DATA
-                .may_load(deps.storage)?
+                .may_load(deps.storage, id)?
                 .ok_or(ContractError::CannotReadAssertionData {})?;
 
-            // let receiver_balance = asset_info.query_balance(
-            let receiver_balance = asset_info.query_pool(
-                &deps.querier,
-                deps.api,
-                deps.api.addr_validate(receiver.as_str())?,
+            // the contract receives the swapped tokens, so its own balance change is exactly the
+            // amount swapped
+            let balance =
+                asset_info.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+            let gross_swap_amount = balance.checked_sub(prev_balance)?;
+
+            commands::observe_swap_price(deps.branch(), &env, id)?;
+            commands::consume_rate_limit(
+                deps.branch(),
+                &env,
+                &asset_info,
+                gross_swap_amount,
+                &recipient,
+            )?;
+
+            // the bridge fee is taken out of the swapped tokens before they are forwarded
+            let (swap_amount, bridge_fee_msg) = commands::collect_bridge_fee(
+                deps.branch(),
+                &env,
+                &asset_info,
+                gross_swap_amount,
+                bridge_fee_share,
             )?;
-            let swap_amount = receiver_balance.checked_sub(prev_balance)?;
 
             if swap_amount < minimum_receive {
                 return Err(ContractError::MinimumReceiveAssertion {
@@ -71,52 +226,228 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
                 });
             }
 
-            TEMP_MIN_ASSERTION_DATA.remove(deps.storage);
+            TEMP_MIN_ASSERTION_DATA.remove(deps.storage, id);
+
+            let bridge_fee_amount = gross_swap_amount.checked_sub(swap_amount)?;
+            commands::record_swap_out(
+                deps.branch(),
+                &env,
+                &asset_info,
+                gross_swap_amount,
+                bridge_fee_amount,
+            )?;
+            commands::record_pool_fees(deps.branch(), &env, &msg.result)?;
+
+            let settlement_event = SwapSettlementEvent {
+                swap_type: SwapType::ExactAmountIn,
+                reply_id: id,
+                recipient: recipient.clone(),
+                token_out_denom: asset_info_to_denom(deps.as_ref(), &asset_info)?,
+                token_out_min_amount: minimum_receive,
+                token_out_amount: gross_swap_amount,
+                bridge_fee_amount,
+                sent_amount: swap_amount,
+            };
+
+            Ok(Response::default()
+                .add_message(send_asset_msg(
+                    deps.as_ref(),
+                    &env,
+                    Asset {
+                        info: asset_info,
+                        amount: swap_amount,
+                    },
+                    recipient,
+                )?)
+                .add_messages(bridge_fee_msg)
+                .add_attribute("action", "assert_minimum_receive")
+                .add_event(settlement_event.into()))
+        }
+        id if TEMP_EXACT_OUT_ASSERTION_DATA.has(deps.storage, id) => {
+            let ExactAmountOutAssertion {
+                asset_info,
+                prev_balance,
+                token_out_amount,
+                recipient,
+                bridge_fee_share,
+            }: ExactAmountOutAssertion = TEMP_EXACT_OUT_ASSERTION_DATA
+                .may_load(deps.storage, id)?
+                .ok_or(ContractError::CannotReadAssertionData {})?;
+
+            // the contract receives the swapped tokens, so its own balance change is exactly the
+            // amount swapped
+            let balance =
+                asset_info.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+            let gross_swap_amount = balance.checked_sub(prev_balance)?;
+
+            commands::observe_swap_price(deps.branch(), &env, id)?;
+            commands::consume_rate_limit(
+                deps.branch(),
+                &env,
+                &asset_info,
+                gross_swap_amount,
+                &recipient,
+            )?;
+
+            // the bridge fee is taken out of the swapped tokens before they are forwarded
+            let (swap_amount, bridge_fee_msg) = commands::collect_bridge_fee(
+                deps.branch(),
+                &env,
+                &asset_info,
+                gross_swap_amount,
+                bridge_fee_share,
+            )?;
+
+            // the offer amount is rounded up to whole token in units to cover the token out amount,
+            // so the swap may return more than it. The surplus was paid for, so it's forwarded to
+            // the recipient along with the token out amount, while the token in left over from
+            // the maximum amount is refunded to the sender by the cosmwasmpool module.
+            if swap_amount < token_out_amount {
+                return Err(ContractError::ExactAmountOutAssertion {
+                    token_out_amount,
+                    swap_amount,
+                });
+            }
+
+            TEMP_EXACT_OUT_ASSERTION_DATA.remove(deps.storage, id);
 
-            Ok(Response::default().add_attribute("action", "assert_minimum_receive"))
+            let bridge_fee_amount = gross_swap_amount.checked_sub(swap_amount)?;
+            commands::record_swap_out(
+                deps.branch(),
+                &env,
+                &asset_info,
+                gross_swap_amount,
+                bridge_fee_amount,
+            )?;
+            commands::record_pool_fees(deps.branch(), &env, &msg.result)?;
+
+            let settlement_event = SwapSettlementEvent {
+                swap_type: SwapType::ExactAmountOut,
+                reply_id: id,
+                recipient: recipient.clone(),
+                token_out_denom: asset_info_to_denom(deps.as_ref(), &asset_info)?,
+                token_out_min_amount: token_out_amount,
+                token_out_amount: gross_swap_amount,
+                bridge_fee_amount,
+                sent_amount: swap_amount,
+            };
+
+            Ok(Response::default()
+                .add_message(send_asset_msg(
+                    deps.as_ref(),
+                    &env,
+                    Asset {
+                        info: asset_info,
+                        amount: swap_amount,
+                    },
+                    recipient,
+                )?)
+                .add_messages(bridge_fee_msg)
+                .add_attribute("action", "assert_exact_amount_out")
+                .add_event(settlement_event.into()))
+        }
+        id if TEMP_SINGLE_SIDED_JOIN_DATA.has(deps.storage, id) => {
+            commands::provide_single_sided_liquidity(deps, env, id, &msg.result)
+        }
+        id if TEMP_EXIT_POOL_DATA.has(deps.storage, id) => {
+            commands::forward_withdrawn_liquidity(deps, env, id)
         }
         id => Err(StdError::generic_err(format!("Unknown reply ID {}", id)).into()),
     }
 }
 
 #[entry_point]
-pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
     match msg {
-        SudoMsg::SetActive { is_active } => commands::set_active(deps, is_active),
+        SudoMsg::SetActive {
+            is_active,
+            from,
+            until,
+        } => commands::set_active(deps, env, is_active, from, until),
+        SudoMsg::SetDenomPause { denom, pause } => commands::set_denom_pause(deps, denom, pause),
+        SudoMsg::UpdateSlippageConfig {
+            max_spread,
+            use_belief_price,
+        } => commands::update_slippage_config(deps, max_spread, use_belief_price),
+        SudoMsg::UpdateSpotPriceConfig { include_fees } => {
+            commands::update_spot_price_config(deps, include_fees)
+        }
+        SudoMsg::RegisterCw20 {
+            contract_addr,
+            subdenom,
+        } => commands::register_cw20(deps, env, contract_addr, subdenom),
+        SudoMsg::SetMaxSpreadOverride {
+            offer_denom,
+            ask_denom,
+            max_spread,
+        } => commands::set_max_spread_override(deps, offer_denom, ask_denom, max_spread),
+        SudoMsg::SetSwapRoute {
+            offer_denom,
+            ask_denom,
+            operations,
+        } => commands::set_swap_route(deps, offer_denom, ask_denom, operations),
+        SudoMsg::SetBridgeFee { share, recipient } => {
+            commands::set_bridge_fee(deps, share, recipient)
+        }
+        SudoMsg::SetBridgeFeeExemption { sender, exempt } => {
+            commands::set_bridge_fee_exemption(deps, sender, exempt)
+        }
+        SudoMsg::SetSenderAccessMode { mode } => commands::set_sender_access_mode(deps, mode),
+        SudoMsg::UpdateSenderAccessList { add, remove } => {
+            commands::update_sender_access_list(deps, add, remove)
+        }
+        SudoMsg::SetRateLimit { denom, rate_limit } => {
+            commands::set_rate_limit(deps, denom, rate_limit)
+        }
+        SudoMsg::UpdateCircuitBreakerConfig { config } => {
+            commands::update_circuit_breaker_config(deps, config)
+        }
         SudoMsg::SwapExactAmountIn {
             sender,
             token_in,
             token_out_denom,
             token_out_min_amount,
-            ..
+            swap_fee,
         } => commands::swap_exact_amount_in(
             deps,
+            env,
             sender,
             token_in,
             token_out_denom,
             token_out_min_amount,
+            swap_fee,
         ),
         SudoMsg::SwapExactAmountOut {
             sender,
             token_out,
             token_in_denom,
             token_in_max_amount,
-            ..
+            swap_fee,
         } => commands::swap_exact_amount_out(
             deps,
+            env,
             sender,
             token_out,
             token_in_max_amount,
             token_in_denom,
+            swap_fee,
         ),
     }
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetSwapFee {} => Ok(to_json_binary(&queries::get_swap_fee(deps)?)?),
-        QueryMsg::IsActive {} => Ok(to_json_binary(&queries::is_active(deps)?)?),
+        QueryMsg::IsActive {} => Ok(to_json_binary(&queries::is_active(deps, env)?)?),
+        QueryMsg::GetActiveStatus {} => {
+            Ok(to_json_binary(&queries::get_active_status(deps, env)?)?)
+        }
+        QueryMsg::GetSwapDirections {} => {
+            Ok(to_json_binary(&queries::get_swap_directions(deps, env)?)?)
+        }
+        QueryMsg::GetTotalShares {} => Ok(to_json_binary(&queries::get_total_shares(deps)?)?),
+        QueryMsg::GetShareDenom {} => Ok(to_json_binary(&queries::get_share_denom(deps)?)?),
         QueryMsg::GetTotalPoolLiquidity {} => {
             Ok(to_json_binary(&queries::get_total_pool_liquidity(deps)?)?)
         }
@@ -125,49 +456,121 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             base_asset_denom,
         } => Ok(to_json_binary(&queries::spot_price(
             deps,
+            env,
+            quote_asset_denom,
+            base_asset_denom,
+        )?)?),
+        QueryMsg::PreciseSpotPrice {
+            quote_asset_denom,
+            base_asset_denom,
+        } => Ok(to_json_binary(&queries::precise_spot_price(
+            deps,
+            env,
             quote_asset_denom,
             base_asset_denom,
         )?)?),
         QueryMsg::CalcOutAmtGivenIn {
             token_in,
             token_out_denom,
-            ..
+            swap_fee,
         } => Ok(to_json_binary(&queries::calc_out_amt_given_in(
             deps,
             token_in,
             token_out_denom,
+            swap_fee,
+            None,
         )?)?),
         QueryMsg::CalcInAmtGivenOut {
             token_out,
             token_in_denom,
-            ..
+            swap_fee,
         } => Ok(to_json_binary(&queries::calc_in_amt_given_out(
             deps,
             token_out,
             token_in_denom,
+            swap_fee,
+            None,
         )?)?),
+        QueryMsg::SimulateJoinPool { tokens_in } => Ok(to_json_binary(
+            &queries::simulate_join_pool(deps, env, tokens_in)?,
+        )?),
+        QueryMsg::SimulateExitPool { shares_in } => Ok(to_json_binary(
+            &queries::simulate_exit_pool(deps, shares_in)?,
+        )?),
         QueryMsg::GetConfig {} => Ok(to_json_binary(&queries::get_config(deps)?)?),
+        QueryMsg::GetOwnership {} => Ok(to_json_binary(&queries::get_ownership(deps)?)?),
+        QueryMsg::GetCw20Denoms {} => Ok(to_json_binary(&queries::get_cw20_denoms(deps)?)?),
+        QueryMsg::GetSlippageConfig {} => Ok(to_json_binary(&queries::get_slippage_config(deps)?)?),
+        QueryMsg::GetSpotPriceConfig {} => {
+            Ok(to_json_binary(&queries::get_spot_price_config(deps)?)?)
+        }
+        QueryMsg::GetRateLimits { sender } => Ok(to_json_binary(&queries::get_rate_limits(
+            deps, env, sender,
+        )?)?),
+        QueryMsg::GetCircuitBreaker {} => Ok(to_json_binary(&queries::get_circuit_breaker(deps)?)?),
+        QueryMsg::Stats {} => Ok(to_json_binary(&queries::get_stats(deps)?)?),
+        QueryMsg::VolumeHistory { denom, from, to } => Ok(to_json_binary(
+            &queries::get_volume_history(deps, denom, from, to)?,
+        )?),
+        QueryMsg::Twap {
+            quote_asset_denom,
+            base_asset_denom,
+            start_time,
+            end_time,
+        } => Ok(to_json_binary(&queries::get_twap(
+            deps,
+            env,
+            quote_asset_denom,
+            base_asset_denom,
+            start_time,
+            end_time,
+        )?)?),
+        QueryMsg::GetSwapRoutes {} => Ok(to_json_binary(&queries::get_swap_routes(deps)?)?),
+        QueryMsg::GetBridgeFee {} => Ok(to_json_binary(&queries::get_bridge_fee(deps)?)?),
+        QueryMsg::GetSenderAccessList { start_after, limit } => Ok(to_json_binary(
+            &queries::get_sender_access_list(deps, start_after, limit)?,
+        )?),
+        QueryMsg::MigrationPlan { target_version } => Ok(to_json_binary(
+            &queries::get_migration_plan(deps, target_version)?,
+        )?),
     }
 }
 
 #[entry_point]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    check_contract_name(deps.storage, CONTRACT_NAME.to_string())?;
-
+pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     let version: Version = CONTRACT_VERSION.parse()?;
-    let storage_version: Version = get_contract_version(deps.storage)?.version.parse()?;
+    let (storage_version, steps) = migrations::plan_migration(deps.storage, &version)?;
+    for step in &steps {
+        (step.migrate)(deps.branch(), &env, &msg)?;
+    }
 
-    if storage_version >= version {
-        return Err(MigrateInvalidVersion {
-            current_version: storage_version,
-            new_version: version,
-        });
+    // governance can take over the ownership of the contract when migrating it
+    if let Some(owner) = msg.owner.as_ref() {
+        let mut config = CONFIG.load(deps.storage)?;
+        config.owner = deps.api.addr_validate(owner)?;
+        CONFIG.save(deps.storage, &config)?;
+        PENDING_OWNER.remove(deps.storage);
     }
 
-    if storage_version == Version::parse("1.0.0")? {
-        IS_ACTIVE.save(deps.storage, &true)?;
+    // governance can re-point the contract to a new White Whale pool holding the same assets
+    if let Some(white_whale_pool) = msg.white_whale_pool.as_ref() {
+        let mut config = CONFIG.load(deps.storage)?;
+        commands::repoint_white_whale_pool(deps.as_ref(), &env, &mut config, white_whale_pool)?;
+        CONFIG.save(deps.storage, &config)?;
     }
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    Ok(Response::default())
+    Ok(Response::default().add_attributes(vec![
+        ("action", "migrate".to_string()),
+        ("from_version", storage_version.to_string()),
+        ("to_version", version.to_string()),
+        (
+            "steps",
+            steps
+                .iter()
+                .map(|step| step.version)
+                .collect::<Vec<&str>>()
+                .join(","),
+        ),
+    ]))
 }
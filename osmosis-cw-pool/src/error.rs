@@ -1,5 +1,5 @@
-use cosmwasm_std::{OverflowError, StdError, Uint128};
-use cw_utils::ParseReplyError;
+use cosmwasm_std::{CheckedFromRatioError, Decimal, Decimal256, OverflowError, StdError, Uint128};
+use cw_utils::{ParseReplyError, PaymentError};
 use semver::Version;
 use thiserror::Error;
 
@@ -22,6 +22,14 @@ pub enum ContractError {
         swap_amount: Uint128,
     },
 
+    #[error(
+        "SwapExactAmountOut returned less than expected. Token out amount: {token_out_amount}, received amount after swap: {swap_amount}"
+    )]
+    ExactAmountOutAssertion {
+        token_out_amount: Uint128,
+        swap_amount: Uint128,
+    },
+
     #[error(
         "SwapExactAmountOut used more tokens than allowed. Maximum token in amount: {token_in_max_amount}, token in used: {token_in_used}"
     )]
@@ -33,6 +41,9 @@ pub enum ContractError {
     #[error("{0}")]
     OverflowError(#[from] OverflowError),
 
+    #[error("{0}")]
+    CheckedFromRatioError(#[from] CheckedFromRatioError),
+
     #[error("Cannot read assertion data")]
     CannotReadAssertionData,
 
@@ -45,14 +56,115 @@ pub enum ContractError {
         current_version: Version,
     },
 
+    #[error("Attempt to migrate to version {target_version}, but this code can only migrate up to version {latest_version}")]
+    UnknownMigrationTarget {
+        target_version: Version,
+        latest_version: Version,
+    },
+
     #[error("{0}")]
     ParseReplyError(#[from] ParseReplyError),
 
+    #[error("{0}")]
+    PaymentError(#[from] PaymentError),
+
     #[error("Can't swap zero amount")]
     ZeroAmount,
 
     #[error("The pool is currently inactive")]
     InactivePool,
+
+    #[error("The active window must end after it starts and after the current block time")]
+    InvalidActiveWindow,
+
+    #[error("Swapping {token_in_denom} for {token_out_denom} is paused")]
+    SwapDirectionPaused {
+        token_in_denom: String,
+        token_out_denom: String,
+    },
+
+    #[error("The CW20 token {contract_addr} is already registered")]
+    Cw20AlreadyRegistered { contract_addr: String },
+
+    #[error("The CW20 token {contract_addr} is not registered")]
+    UnregisteredCw20 { contract_addr: String },
+
+    #[error("The denom {denom} doesn't wrap any CW20 token")]
+    UnregisteredWrappedDenom { denom: String },
+
+    #[error(
+        "The White Whale pool {white_whale_pool} doesn't hold the same assets as the current one"
+    )]
+    PoolAssetsMismatch { white_whale_pool: String },
+
+    #[error("Either all the pool assets or, for pairs, a single one must be provided")]
+    InvalidJoinPoolTokens,
+
+    #[error("The token sent is not the LP token of the pool")]
+    InvalidLpToken,
+
+    #[error("The router is not set")]
+    RouterNotSet,
+
+    #[error("Invalid swap route from {offer_denom} to {ask_denom}")]
+    InvalidSwapRoute {
+        offer_denom: String,
+        ask_denom: String,
+    },
+
+    #[error("Routed swaps only support swapping an exact amount in")]
+    RoutedSwapExactAmountOut,
+
+    #[error("Invalid bridge fee {share}, it can't be higher than {max_share}")]
+    InvalidBridgeFee { share: Decimal, max_share: Decimal },
+
+    #[error("The sender {sender} is not allowed to swap")]
+    SenderNotAllowed { sender: String },
+
+    #[error("The circuit breaker price changes must be positive")]
+    InvalidCircuitBreakerConfig,
+
+    #[error(
+        "The swap moves the spot price by {price_change}, more than the allowed {max_price_change}"
+    )]
+    CircuitBreakerTripped {
+        price_change: Decimal256,
+        max_price_change: Decimal,
+    },
+
+    #[error("The rate limit window and volumes must be positive")]
+    InvalidRateLimit,
+
+    #[error(
+        "Swapping out {volume}{denom} in the current window exceeds the rate limit of {max_volume}"
+    )]
+    RateLimitExceeded {
+        denom: String,
+        volume: Uint128,
+        max_volume: Uint128,
+    },
+
+    #[error(
+        "Swapping out {volume}{denom} in the current window exceeds the rate limit of {max_volume} for {sender}"
+    )]
+    SenderRateLimitExceeded {
+        sender: String,
+        denom: String,
+        volume: Uint128,
+        max_volume: Uint128,
+    },
+
+    #[error("Invalid gauge: {reason}")]
+    InvalidGauge { reason: String },
+
+    #[error("There's no pending ownership proposal")]
+    NoPendingOwnershipProposal,
+
+    #[error("Invalid max spread {max_spread}, it can't be higher than {max_allowed_spread}")]
+    InvalidMaxSpread {
+        max_spread: Decimal,
+        max_allowed_spread: Decimal,
+    },
 }
 
 impl From<semver::Error> for ContractError {
@@ -0,0 +1,111 @@
+//! Events emitted by the swaps, so they can be reconstructed from events alone.
+//!
+//! Every swap emits a `swap` event when it's dispatched to the White Whale pool or router, and a
+//! `swap_settlement` event once the tokens out are received and forwarded. Both carry the
+//! `swap_type` and the `reply_id` of the swap, which pair them together.
+//!
+//! `swap` attributes:
+//! - `swap_type`: `exact_amount_in` or `exact_amount_out`
+//! - `reply_id`: the id pairing the swap with its settlement
+//! - `sender`: the address swapping
+//! - `token_in_denom`, `token_in_amount`: the tokens swapped in. For exact amount out swaps, the
+//!   amount is the one expected to be needed.
+//! - `token_in_max_amount`: the maximum amount of tokens in allowed
+//! - `token_out_denom`, `token_out_amount`: the tokens expected out, after the bridge fee
+//! - `token_out_min_amount`: the minimum amount of tokens out allowed
+//! - `swap_fee`: the swap fee, including the bridge fee
+//! - `routed`: whether the swap goes through the White Whale router
+//!
+//! `swap_settlement` attributes:
+//! - `swap_type`, `reply_id`: as in the `swap` event
+//! - `recipient`: the address the tokens out are sent to
+//! - `token_out_denom`: the denom of the tokens out
+//! - `token_out_min_amount`: the minimum amount of tokens out allowed
+//! - `token_out_amount`: the amount of tokens out returned by the swap
+//! - `bridge_fee_amount`: the bridge fee taken out of the tokens out
+//! - `sent_amount`: the amount of tokens out sent to the recipient
+
+use cosmwasm_std::{Coin, Decimal, Event, Uint128};
+
+pub(crate) const SWAP_EVENT_TYPE: &str = "swap";
+pub(crate) const SWAP_SETTLEMENT_EVENT_TYPE: &str = "swap_settlement";
+
+/// The kind of swap requested by the cosmwasmpool module
+#[derive(Clone, Copy)]
+pub(crate) enum SwapType {
+    ExactAmountIn,
+    ExactAmountOut,
+}
+
+impl SwapType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SwapType::ExactAmountIn => "exact_amount_in",
+            SwapType::ExactAmountOut => "exact_amount_out",
+        }
+    }
+}
+
+/// Emitted when a swap is dispatched to the White Whale pool or router
+pub(crate) struct SwapEvent {
+    pub swap_type: SwapType,
+    pub reply_id: u64,
+    pub sender: String,
+    pub token_in: Coin,
+    pub token_in_max_amount: Uint128,
+    pub token_out: Coin,
+    pub token_out_min_amount: Uint128,
+    pub swap_fee: Decimal,
+    pub routed: bool,
+}
+
+impl From<SwapEvent> for Event {
+    fn from(event: SwapEvent) -> Self {
+        Event::new(SWAP_EVENT_TYPE).add_attributes(vec![
+            ("swap_type", event.swap_type.as_str().to_string()),
+            ("reply_id", event.reply_id.to_string()),
+            ("sender", event.sender),
+            ("token_in_denom", event.token_in.denom),
+            ("token_in_amount", event.token_in.amount.to_string()),
+            ("token_in_max_amount", event.token_in_max_amount.to_string()),
+            ("token_out_denom", event.token_out.denom),
+            ("token_out_amount", event.token_out.amount.to_string()),
+            (
+                "token_out_min_amount",
+                event.token_out_min_amount.to_string(),
+            ),
+            ("swap_fee", event.swap_fee.to_string()),
+            ("routed", event.routed.to_string()),
+        ])
+    }
+}
+
+/// Emitted when the tokens out of a swap are received and forwarded to the recipient
+pub(crate) struct SwapSettlementEvent {
+    pub swap_type: SwapType,
+    pub reply_id: u64,
+    pub recipient: String,
+    pub token_out_denom: String,
+    pub token_out_min_amount: Uint128,
+    pub token_out_amount: Uint128,
+    pub bridge_fee_amount: Uint128,
+    pub sent_amount: Uint128,
+}
+
+impl From<SwapSettlementEvent> for Event {
+    fn from(event: SwapSettlementEvent) -> Self {
+        Event::new(SWAP_SETTLEMENT_EVENT_TYPE).add_attributes(vec![
+            ("swap_type", event.swap_type.as_str().to_string()),
+            ("reply_id", event.reply_id.to_string()),
+            ("recipient", event.recipient),
+            ("token_out_denom", event.token_out_denom),
+            (
+                "token_out_min_amount",
+                event.token_out_min_amount.to_string(),
+            ),
+            ("token_out_amount", event.token_out_amount.to_string()),
+            ("bridge_fee_amount", event.bridge_fee_amount.to_string()),
+            ("sent_amount", event.sent_amount.to_string()),
+        ])
+    }
+}
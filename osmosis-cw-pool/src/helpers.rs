@@ -0,0 +1,107 @@
+use cosmwasm_std::{
+    to_json_binary, BankMsg, Coin, CosmosMsg, Deps, Env, StdError, StdResult, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgBurn, MsgMint};
+use white_whale_std::pool_network::asset::{Asset, AssetInfo};
+
+use crate::state::{CW20_WRAPPED_DENOMS, WRAPPED_DENOM_CW20S};
+
+/// Gets the White Whale asset info for the given Osmosis denom. Denoms wrapping a CW20 token map
+/// to the CW20 token itself.
+pub(crate) fn denom_to_asset_info(deps: Deps, denom: &str) -> StdResult<AssetInfo> {
+    match WRAPPED_DENOM_CW20S.may_load(deps.storage, denom)? {
+        Some(contract_addr) => Ok(AssetInfo::Token { contract_addr }),
+        None => Ok(AssetInfo::NativeToken {
+            denom: denom.to_string(),
+        }),
+    }
+}
+
+/// Gets the Osmosis denom for the given White Whale asset info. CW20 tokens map to the denom
+/// wrapping them, which must have been registered.
+pub(crate) fn asset_info_to_denom(deps: Deps, asset_info: &AssetInfo) -> StdResult<String> {
+    match asset_info {
+        AssetInfo::NativeToken { denom } => Ok(denom.to_string()),
+        AssetInfo::Token { contract_addr } => CW20_WRAPPED_DENOMS
+            .may_load(deps.storage, contract_addr)?
+            .ok_or_else(|| StdError::generic_err(format!("Token {} not supported", contract_addr))),
+    }
+}
+
+/// Converts White Whale assets into Osmosis coins
+pub(crate) fn assets_to_coins(deps: Deps, assets: &[Asset]) -> StdResult<Vec<Coin>> {
+    assets
+        .iter()
+        .map(|asset| {
+            Ok(Coin {
+                denom: asset_info_to_denom(deps, &asset.info)?,
+                amount: asset.amount,
+            })
+        })
+        .collect()
+}
+
+/// Creates a message minting the denom wrapping the given CW20 token to the recipient
+pub(crate) fn mint_wrapped_token_msg(
+    deps: Deps,
+    env: &Env,
+    asset_info: &AssetInfo,
+    amount: Uint128,
+    recipient: String,
+) -> StdResult<CosmosMsg> {
+    let denom = asset_info_to_denom(deps, asset_info)?;
+
+    Ok(MsgMint {
+        sender: env.contract.address.to_string(),
+        amount: Some(Coin { denom, amount }.into()),
+        mint_to_address: recipient,
+    }
+    .into())
+}
+
+/// Creates a message burning the given wrapped tokens held by the contract
+pub(crate) fn burn_wrapped_token_msg(env: &Env, coin: Coin) -> CosmosMsg {
+    MsgBurn {
+        sender: env.contract.address.to_string(),
+        amount: Some(coin.into()),
+        burn_from_address: env.contract.address.to_string(),
+    }
+    .into()
+}
+
+/// Creates a message sending the given asset held by the contract to the recipient. CW20 tokens
+/// wrapped by the contract are minted as their wrapped denom, as Osmosis only deals with native
+/// denoms, while the rest are transferred as they are.
+pub(crate) fn send_asset_msg(
+    deps: Deps,
+    env: &Env,
+    asset: Asset,
+    recipient: String,
+) -> StdResult<CosmosMsg> {
+    match &asset.info {
+        AssetInfo::NativeToken { denom } => Ok(BankMsg::Send {
+            to_address: recipient,
+            amount: vec![Coin {
+                denom: denom.to_string(),
+                amount: asset.amount,
+            }],
+        }
+        .into()),
+        AssetInfo::Token { contract_addr } => {
+            if CW20_WRAPPED_DENOMS.has(deps.storage, contract_addr) {
+                return mint_wrapped_token_msg(deps, env, &asset.info, asset.amount, recipient);
+            }
+
+            Ok(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient,
+                    amount: asset.amount,
+                })?,
+                funds: vec![],
+            }
+            .into())
+        }
+    }
+}
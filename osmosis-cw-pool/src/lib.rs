@@ -1,8 +1,12 @@
 pub mod commands;
 pub mod contract;
 mod error;
+mod events;
+mod helpers;
+mod migrations;
 pub mod msg;
 pub mod queries;
+mod stableswap_math;
 pub mod state;
 
 pub use crate::error::ContractError;
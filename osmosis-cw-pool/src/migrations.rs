@@ -0,0 +1,398 @@
+//! The state migrations run by the migrate entry point.
+//!
+//! Each step brings the state stored by the versions prior to its own to the layout used from it
+//! on. When migrating, the steps whose version is higher than the stored one, up to the version
+//! being migrated to, run in order.
+
+use cosmwasm_std::{DepsMut, Env, StdError, Storage};
+use cw2::get_contract_version;
+use semver::Version;
+use white_whale_std::migrate_guards::check_contract_name;
+
+use crate::commands;
+use crate::contract::{CONTRACT_NAME, CONTRACT_VERSION};
+use crate::error::ContractError;
+use crate::msg::{Config, MigrateMsg, PoolType};
+use crate::queries;
+use crate::state::{
+    CONFIG, CONFIG_V102, IS_ACTIVE, SLIPPAGE_CONFIG, SPOT_PRICE_CONFIG,
+    TEMP_MIN_ASSERTION_DATA_V102,
+};
+
+/// A step migrating the state to the layout of a given version
+#[derive(Debug)]
+pub(crate) struct MigrationStep {
+    /// The version introducing the state layout the step migrates to
+    pub version: &'static str,
+    pub description: &'static str,
+    /// The state keys the step writes or removes
+    pub affected_keys: &'static [&'static str],
+    pub migrate: fn(DepsMut, &Env, &MigrateMsg) -> Result<(), ContractError>,
+}
+
+/// The migration steps, sorted by version
+pub(crate) static MIGRATION_STEPS: [MigrationStep; 2] = [
+    MigrationStep {
+        version: "1.0.1",
+        description: "Activates the pool",
+        affected_keys: &["is_active"],
+        migrate: migrate_to_v101,
+    },
+    MigrationStep {
+        version: "1.4.0",
+        description: "Sets the owner, the slippage and spot price configs and the LP token of the \
+            pool, and drops the shared swap assertion data",
+        affected_keys: &[
+            "slippage_config",
+            "spot_price_config",
+            "config",
+            "temp_min_assertion_data",
+        ],
+        migrate: migrate_to_v140,
+    },
+];
+
+/// Checks the stored contract can be migrated to `version`, returning the stored version and the
+/// steps to run. Used by the migrate entry point, and to plan migrations ahead.
+pub(crate) fn plan_migration(
+    storage: &dyn Storage,
+    version: &Version,
+) -> Result<(Version, Vec<&'static MigrationStep>), ContractError> {
+    check_contract_name(storage, CONTRACT_NAME.to_string())?;
+
+    let storage_version: Version = get_contract_version(storage)?.version.parse()?;
+    if storage_version >= *version {
+        return Err(ContractError::MigrateInvalidVersion {
+            current_version: storage_version,
+            new_version: version.clone(),
+        });
+    }
+
+    // the steps of later versions aren't known by this code
+    let latest_version: Version = CONTRACT_VERSION.parse()?;
+    if *version > latest_version {
+        return Err(ContractError::UnknownMigrationTarget {
+            target_version: version.clone(),
+            latest_version,
+        });
+    }
+
+    let steps = pending_steps(&storage_version, version)?;
+    Ok((storage_version, steps))
+}
+
+/// Gets the steps migrating the state stored by `storage_version` to the one of `version`, in
+/// the order they must run.
+pub(crate) fn pending_steps(
+    storage_version: &Version,
+    version: &Version,
+) -> Result<Vec<&'static MigrationStep>, ContractError> {
+    let mut steps = vec![];
+    for step in MIGRATION_STEPS.iter() {
+        let step_version: Version = step.version.parse()?;
+        if step_version > *storage_version && step_version <= *version {
+            steps.push(step);
+        }
+    }
+
+    Ok(steps)
+}
+
+/// 1.0.0 didn't store whether the pool is active.
+fn migrate_to_v101(deps: DepsMut, _env: &Env, _msg: &MigrateMsg) -> Result<(), ContractError> {
+    IS_ACTIVE.save(deps.storage, &true)?;
+    Ok(())
+}
+
+/// 1.0.x only stored the White Whale pool in its config, and shared the swap assertion data
+/// between swaps. The owner must be given, while the slippage and spot price configs are the ones
+/// given, or the default ones.
+fn migrate_to_v140(deps: DepsMut, env: &Env, msg: &MigrateMsg) -> Result<(), ContractError> {
+    let slippage_config = msg.slippage_config.clone().unwrap_or_default();
+    commands::validate_max_spread(slippage_config.max_spread)?;
+    SLIPPAGE_CONFIG.save(deps.storage, &slippage_config)?;
+
+    SPOT_PRICE_CONFIG.save(
+        deps.storage,
+        &msg.spot_price_config.clone().unwrap_or_default(),
+    )?;
+
+    let owner = msg.owner.as_ref().ok_or_else(|| {
+        StdError::generic_err("The owner is required to migrate from a version without owner")
+    })?;
+    let config = CONFIG_V102.load(deps.storage)?;
+
+    // 1.0.x only supported pairs
+    let pool_type = PoolType::Pair;
+    let lp_token =
+        queries::query_pool_info(deps.as_ref(), env, &config.white_whale_pool, &pool_type)?
+            .liquidity_token;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_validate(owner)?,
+            white_whale_pool: config.white_whale_pool,
+            pool_type,
+            lp_token,
+            router: None,
+        },
+    )?;
+
+    // the assertion data is now keyed per swap, so the shared one is dropped
+    TEMP_MIN_ASSERTION_DATA_V102.remove(deps.storage);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{
+        to_json_binary, Addr, ContractResult, Decimal, OwnedDeps, SystemResult, WasmQuery,
+    };
+    use white_whale_std::pool_network::asset::{AssetInfo, PairInfo, PairType};
+
+    use crate::msg::{SlippageConfig, SpotPriceConfig};
+
+    use super::*;
+
+    const WHITE_WHALE_POOL: &str = "white_whale_pool";
+    const LP_TOKEN: &str = "factory/white_whale_pool/uLP";
+
+    /// Mocks the White Whale pair the contract points to
+    fn mock_deps() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == WHITE_WHALE_POOL => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&PairInfo {
+                        asset_infos: [
+                            AssetInfo::NativeToken {
+                                denom: "uosmo".to_string(),
+                            },
+                            AssetInfo::NativeToken {
+                                denom: "uwhale".to_string(),
+                            },
+                        ],
+                        contract_addr: WHITE_WHALE_POOL.to_string(),
+                        liquidity_token: AssetInfo::NativeToken {
+                            denom: LP_TOKEN.to_string(),
+                        },
+                        asset_decimals: [6, 6],
+                        pair_type: PairType::ConstantProduct,
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => panic!("Unexpected query {:?}", query),
+        });
+        deps
+    }
+
+    /// Stores the state of a 1.0.x contract as serialized by it, in the middle of a swap
+    fn save_v102_state(deps: DepsMut, version: &str) {
+        cw2::set_contract_version(deps.storage, CONTRACT_NAME, version).unwrap();
+        deps.storage
+            .set(b"config", br#"{"white_whale_pool":"white_whale_pool"}"#);
+        deps.storage.set(
+            b"temp_min_assertion_data",
+            br#"{"asset_info":{"native_token":{"denom":"uwhale"}},"prev_balance":"0","minimum_receive":"1","receiver":"sender"}"#,
+        );
+    }
+
+    fn migrate_msg(owner: Option<&str>) -> MigrateMsg {
+        MigrateMsg {
+            owner: owner.map(str::to_string),
+            white_whale_pool: None,
+            slippage_config: None,
+            spot_price_config: None,
+        }
+    }
+
+    fn versions(steps: Vec<&MigrationStep>) -> Vec<&str> {
+        steps.into_iter().map(|step| step.version).collect()
+    }
+
+    #[test]
+    fn steps_are_sorted() {
+        let step_versions = MIGRATION_STEPS
+            .iter()
+            .map(|step| step.version.parse::<Version>().unwrap())
+            .collect::<Vec<Version>>();
+
+        assert!(step_versions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(
+            step_versions.last().unwrap(),
+            &env!("CARGO_PKG_VERSION").parse::<Version>().unwrap()
+        );
+    }
+
+    #[test]
+    fn pending_steps_start_from_the_stored_version() {
+        let version: Version = "1.4.0".parse().unwrap();
+
+        assert_eq!(
+            versions(pending_steps(&"1.0.0".parse().unwrap(), &version).unwrap()),
+            vec!["1.0.1", "1.4.0"]
+        );
+        assert_eq!(
+            versions(pending_steps(&"1.0.2".parse().unwrap(), &version).unwrap()),
+            vec!["1.4.0"]
+        );
+        assert_eq!(
+            versions(pending_steps(&"1.0.0".parse().unwrap(), &"1.0.2".parse().unwrap()).unwrap()),
+            vec!["1.0.1"]
+        );
+        assert!(pending_steps(&version, &version).unwrap().is_empty());
+    }
+
+    #[test]
+    fn plan_migration_runs_the_migrate_checks() {
+        let mut deps = mock_deps();
+        let version: Version = "1.4.0".parse().unwrap();
+
+        cw2::set_contract_version(&mut deps.storage, "crates.io:other_contract", "1.0.2").unwrap();
+        assert!(plan_migration(&deps.storage, &version).is_err());
+
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "1.0.2").unwrap();
+        let err = plan_migration(&deps.storage, &"1.0.2".parse().unwrap()).unwrap_err();
+        assert!(matches!(err, ContractError::MigrateInvalidVersion { .. }));
+
+        let err = plan_migration(&deps.storage, &"99.0.0".parse().unwrap()).unwrap_err();
+        assert!(matches!(err, ContractError::UnknownMigrationTarget { .. }));
+
+        let (storage_version, steps) = plan_migration(&deps.storage, &version).unwrap();
+        assert_eq!(storage_version, "1.0.2".parse::<Version>().unwrap());
+        assert_eq!(versions(steps), vec!["1.4.0"]);
+    }
+
+    #[test]
+    fn migration_plan_query_reports_the_affected_keys() {
+        let mut deps = mock_deps();
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "1.0.0").unwrap();
+
+        let plan = queries::get_migration_plan(deps.as_ref(), "1.4.0".to_string()).unwrap();
+        assert_eq!(plan.stored_version, "1.0.0");
+        assert_eq!(plan.target_version, "1.4.0");
+        assert_eq!(
+            plan.steps
+                .iter()
+                .map(|step| step.version.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["1.0.1", "1.4.0"]
+        );
+        assert_eq!(plan.steps[1].affected_keys.len(), 4);
+        assert_eq!(
+            plan.affected_keys,
+            vec![
+                "contract_info",
+                "is_active",
+                "slippage_config",
+                "spot_price_config",
+                "config",
+                "temp_min_assertion_data",
+            ]
+        );
+
+        assert!(queries::get_migration_plan(deps.as_ref(), "1.0.0".to_string()).is_err());
+        assert!(queries::get_migration_plan(deps.as_ref(), "latest".to_string()).is_err());
+    }
+
+    #[test]
+    fn migrate_to_v101_activates_the_pool() {
+        let mut deps = mock_deps();
+
+        migrate_to_v101(deps.as_mut(), &mock_env(), &migrate_msg(None)).unwrap();
+
+        assert!(IS_ACTIVE.load(&deps.storage).unwrap());
+    }
+
+    #[test]
+    fn migrate_to_v140_requires_an_owner() {
+        let mut deps = mock_deps();
+        save_v102_state(deps.as_mut(), "1.0.2");
+
+        let err = migrate_to_v140(deps.as_mut(), &mock_env(), &migrate_msg(None)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: The owner is required to migrate from a version without owner"
+        );
+    }
+
+    #[test]
+    fn migrate_to_v140_validates_the_slippage_config() {
+        let mut deps = mock_deps();
+        save_v102_state(deps.as_mut(), "1.0.2");
+
+        let msg = MigrateMsg {
+            slippage_config: Some(SlippageConfig {
+                max_spread: Decimal::percent(60),
+                use_belief_price: false,
+            }),
+            ..migrate_msg(Some("owner"))
+        };
+        let err = migrate_to_v140(deps.as_mut(), &mock_env(), &msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidMaxSpread { .. }));
+    }
+
+    #[test]
+    fn migrate_to_v140_migrates_the_v102_state() {
+        let mut deps = mock_deps();
+        save_v102_state(deps.as_mut(), "1.0.2");
+
+        let slippage_config = SlippageConfig {
+            max_spread: Decimal::percent(5),
+            use_belief_price: true,
+        };
+        let msg = MigrateMsg {
+            slippage_config: Some(slippage_config.clone()),
+            spot_price_config: Some(SpotPriceConfig { include_fees: true }),
+            ..migrate_msg(Some("owner"))
+        };
+        migrate_to_v140(deps.as_mut(), &mock_env(), &msg).unwrap();
+
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap(),
+            Config {
+                owner: Addr::unchecked("owner"),
+                white_whale_pool: Addr::unchecked(WHITE_WHALE_POOL),
+                pool_type: PoolType::Pair,
+                lp_token: AssetInfo::NativeToken {
+                    denom: LP_TOKEN.to_string()
+                },
+                router: None,
+            }
+        );
+        assert_eq!(
+            SLIPPAGE_CONFIG.load(&deps.storage).unwrap(),
+            slippage_config
+        );
+        assert!(SPOT_PRICE_CONFIG.load(&deps.storage).unwrap().include_fees);
+        assert!(deps.storage.get(b"temp_min_assertion_data").is_none());
+    }
+
+    #[test]
+    fn steps_run_in_sequence_from_v100() {
+        let mut deps = mock_deps();
+        save_v102_state(deps.as_mut(), "1.0.0");
+
+        let (_, steps) = plan_migration(&deps.storage, &"1.4.0".parse().unwrap()).unwrap();
+        let msg = migrate_msg(Some("owner"));
+        for step in steps {
+            (step.migrate)(deps.as_mut(), &mock_env(), &msg).unwrap();
+        }
+
+        assert!(IS_ACTIVE.load(&deps.storage).unwrap());
+        assert_eq!(
+            SLIPPAGE_CONFIG.load(&deps.storage).unwrap(),
+            SlippageConfig::default()
+        );
+        assert_eq!(
+            SPOT_PRICE_CONFIG.load(&deps.storage).unwrap(),
+            SpotPriceConfig::default()
+        );
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(config.owner, Addr::unchecked("owner"));
+        assert_eq!(config.pool_type, PoolType::Pair);
+    }
+}
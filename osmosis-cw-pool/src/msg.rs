@@ -1,22 +1,204 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Coin, Decimal, Uint128};
+use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, Timestamp, Uint128};
+use cw20::Cw20ReceiveMsg;
+use cw_utils::Duration;
 use osmosis_std::types::osmosis::incentives::MsgCreateGauge;
-use white_whale_std::pool_network::asset::AssetInfo;
+use white_whale_std::pool_network::asset::{Asset, AssetInfo};
+use white_whale_std::pool_network::router::SwapOperation;
 
 #[cw_serde]
 pub struct InstantiateMsg {
+    /// The owner of the contract, allowed to manage its config. Defaults to the instantiator, i.e.
+    /// the cosmwasmpool module when the pool is created through it.
+    pub owner: Option<String>,
     pub white_whale_pool: String,
     pub after_pool_created: Option<AfterPoolCreated>,
+    /// The slippage policy applied to swaps. Defaults to a 30% max spread without belief price.
+    pub slippage_config: Option<SlippageConfig>,
+    /// How spot prices are computed. Defaults to excluding the pool fees.
+    pub spot_price_config: Option<SpotPriceConfig>,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Receives CW20 tokens, used to wrap them into their tokenfactory denom.
+    Receive(Cw20ReceiveMsg),
+    /// Unwraps the tokenfactory denom sent along with the message back into the CW20 token it wraps.
+    /// If no receiver is provided, the CW20 tokens are sent to the sender.
+    Unwrap { receiver: Option<String> },
+    /// Updates the config of the contract. The new White Whale pool, if any, must hold the same
+    /// assets as the current one. Only the owner can do this.
+    UpdateConfig {
+        white_whale_pool: Option<String>,
+        /// The White Whale router used for routed swaps
+        router: Option<String>,
+    },
+    /// Sets the active status of the pool. If `from` or `until` are set, the pool only has that
+    /// status between those times, and the opposite one outside of them, e.g. to pause it for a
+    /// while or to activate it at launch. Only the owner can do this.
+    SetActive {
+        is_active: bool,
+        from: Option<Timestamp>,
+        until: Option<Timestamp>,
+    },
+    /// Pauses the swaps selling or buying `denom`, or resumes them, while the other swaps stay
+    /// enabled. Only the owner can do this.
+    SetDenomPause { denom: String, pause: DenomPause },
+    /// Updates the default slippage policy applied to swaps. Only the owner can do this.
+    UpdateSlippageConfig {
+        max_spread: Option<Decimal>,
+        use_belief_price: Option<bool>,
+    },
+    /// Overrides the default max spread for swaps going from `offer_denom` to `ask_denom`. If
+    /// `max_spread` is `None`, the override is removed. Only the owner can do this.
+    SetMaxSpreadOverride {
+        offer_denom: String,
+        ask_denom: String,
+        max_spread: Option<Decimal>,
+    },
+    /// Updates how spot prices are computed. Only the owner can do this.
+    UpdateSpotPriceConfig { include_fees: bool },
+    /// Routes the swaps going from `offer_denom` to `ask_denom` through the given White Whale swap
+    /// operations, executed by the router. If `operations` is `None`, the route is removed. Only
+    /// the owner can do this.
+    SetSwapRoute {
+        offer_denom: String,
+        ask_denom: String,
+        operations: Option<Vec<SwapOperation>>,
+    },
+    /// Sets the share of the amount out charged as bridge fee on swaps, sent to the recipient. A zero
+    /// share disables the bridge fee. Only the owner can do this.
+    SetBridgeFee { share: Decimal, recipient: String },
+    /// Exempts the given sender from the bridge fee, or removes its exemption. Only the owner can
+    /// do this.
+    SetBridgeFeeExemption { sender: String, exempt: bool },
+    /// Sets how the sender access list is applied to swaps. If `mode` is `None`, any sender can
+    /// swap. Only the owner can do this.
+    SetSenderAccessMode { mode: Option<SenderAccessMode> },
+    /// Adds senders to and removes senders from the sender access list. Only the owner can do
+    /// this.
+    UpdateSenderAccessList {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Sets the circuit breaker refusing the swaps that move the spot price beyond the configured
+    /// bands. If `config` is `None`, the circuit breaker is disabled. Only the owner can do this.
+    UpdateCircuitBreakerConfig {
+        config: Option<CircuitBreakerConfig>,
+    },
+    /// Caps the volume of `denom` that can be swapped out of the pool per window. If `rate_limit`
+    /// is `None`, the cap is removed. Only the owner can do this.
+    SetRateLimit {
+        denom: String,
+        rate_limit: Option<RateLimit>,
+    },
+    /// Creates the given incentive gauges, owned by the contract. The funds sent along with the
+    /// message must match the gauge coins plus the creation fee of each gauge. Only the owner can
+    /// do this.
+    CreateGauges { msgs: Vec<MsgCreateGauge> },
+    /// Adds the given rewards to the incentive gauge, which must be owned by the contract. The
+    /// funds sent along with the message must match the rewards plus the fee. Only the owner can
+    /// do this.
+    AddToGauge { gauge_id: u64, rewards: Vec<Coin> },
+    /// Proposes a new owner for the contract, which becomes the owner once it accepts the
+    /// ownership. Only the owner can do this.
+    ProposeNewOwner { new_owner: String },
+    /// Drops the pending ownership proposal. Only the owner can do this.
+    DropOwnershipProposal {},
+    /// Accepts the ownership of the contract. Only the proposed owner can do this.
+    AcceptOwnership {},
+    /// Provides the tokens sent along with the message as liquidity to the White Whale pool. Either
+    /// all the pool assets or, for pairs, a single one can be sent. Single sided joins swap half of
+    /// the tokens for the other asset before providing. The LP shares are sent to the receiver, or
+    /// to the sender if no receiver is provided.
+    JoinPool {
+        slippage_tolerance: Option<Decimal>,
+        receiver: Option<String>,
+    },
+    /// Withdraws the liquidity of the LP shares sent along with the message from the White Whale
+    /// pool. The assets are sent to the receiver, or to the sender if no receiver is provided.
+    ExitPool { receiver: Option<String> },
+}
+
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// Wraps the CW20 tokens sent into their tokenfactory denom, which is minted to the receiver.
+    /// If no receiver is provided, the wrapped tokens are minted to the sender.
+    Wrap { receiver: Option<String> },
+    /// Withdraws the liquidity of the CW20 LP shares sent from the White Whale pool. The assets are
+    /// sent to the receiver, or to the sender if no receiver is provided.
+    ExitPool { receiver: Option<String> },
 }
 
 #[cw_serde]
 pub enum SudoMsg {
-    /// SetActive sets the active status of the pool.
-    SetActive { is_active: bool },
+    /// SetActive sets the active status of the pool. If `from` or `until` are set, the pool only
+    /// has that status between those times, and the opposite one outside of them.
+    SetActive {
+        is_active: bool,
+        from: Option<Timestamp>,
+        until: Option<Timestamp>,
+    },
+    /// SetDenomPause pauses the swaps selling or buying `denom`, or resumes them, while the other
+    /// swaps stay enabled.
+    SetDenomPause { denom: String, pause: DenomPause },
+    /// UpdateSlippageConfig updates the default slippage policy applied to swaps.
+    UpdateSlippageConfig {
+        max_spread: Option<Decimal>,
+        use_belief_price: Option<bool>,
+    },
+    /// UpdateSpotPriceConfig updates how spot prices are computed.
+    UpdateSpotPriceConfig { include_fees: bool },
+    /// RegisterCw20 creates the tokenfactory denom `factory/{contract}/{subdenom}` wrapping the given
+    /// CW20 token of the pool, so it can be traded on Osmosis. The contract must hold enough funds to
+    /// pay for the denom creation fee.
+    RegisterCw20 {
+        contract_addr: String,
+        subdenom: String,
+    },
+    /// SetMaxSpreadOverride overrides the default max spread for swaps going from `offer_denom`
+    /// to `ask_denom`. If `max_spread` is `None`, the override is removed.
+    SetMaxSpreadOverride {
+        offer_denom: String,
+        ask_denom: String,
+        max_spread: Option<Decimal>,
+    },
+    /// SetSwapRoute routes the swaps going from `offer_denom` to `ask_denom` through the given
+    /// White Whale swap operations, executed by the router. If `operations` is `None`, the route
+    /// is removed.
+    SetSwapRoute {
+        offer_denom: String,
+        ask_denom: String,
+        operations: Option<Vec<SwapOperation>>,
+    },
+    /// SetBridgeFee sets the share of the amount out charged as bridge fee on swaps, sent to the
+    /// recipient. A zero share disables the bridge fee.
+    SetBridgeFee { share: Decimal, recipient: String },
+    /// SetBridgeFeeExemption exempts the given sender from the bridge fee, or removes its exemption.
+    SetBridgeFeeExemption { sender: String, exempt: bool },
+    /// SetSenderAccessMode sets how the sender access list is applied to swaps. If `mode` is
+    /// `None`, any sender can swap.
+    SetSenderAccessMode { mode: Option<SenderAccessMode> },
+    /// UpdateSenderAccessList adds senders to and removes senders from the sender access list.
+    UpdateSenderAccessList {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// UpdateCircuitBreakerConfig sets the circuit breaker refusing the swaps that move the spot
+    /// price beyond the configured bands. If `config` is `None`, the circuit breaker is disabled.
+    UpdateCircuitBreakerConfig {
+        config: Option<CircuitBreakerConfig>,
+    },
+    /// SetRateLimit caps the volume of `denom` that can be swapped out of the pool per window. If
+    /// `rate_limit` is `None`, the cap is removed.
+    SetRateLimit {
+        denom: String,
+        rate_limit: Option<RateLimit>,
+    },
     /// SwapExactAmountIn swaps an exact amount of tokens in for as many tokens out as possible.
     /// The amount of tokens out is determined by the current exchange rate and the swap fee.
     /// The user specifies a minimum amount of tokens out, and the transaction will revert if that amount of tokens
-    /// is not received.
+    /// is not received. The swap fee must match the one returned by GetSwapFee.
     SwapExactAmountIn {
         sender: String,
         token_in: Coin,
@@ -27,7 +209,7 @@ pub enum SudoMsg {
     /// SwapExactAmountOut swaps as many tokens in as possible for an exact amount of tokens out.
     /// The amount of tokens in is determined by the current exchange rate and the swap fee.
     /// The user specifies a maximum amount of tokens in, and the transaction will revert if that amount of tokens
-    /// is exceeded.
+    /// is exceeded. The swap fee must match the one returned by GetSwapFee.
     SwapExactAmountOut {
         sender: String,
         token_in_denom: String,
@@ -45,6 +227,8 @@ pub enum QueryMsg {
     /// (prior TWAPs, network downtime, other pool states, etc.)
     /// This is intended to be fee that is collected by liquidity providers.
     /// If the contract provider wants to collect fee for itself, it should implement its own fee collection mechanism.
+    /// The White Whale pool charges its protocol, burn and Osmosis fees on the swapped amount along
+    /// with the swap fee, so all of them are included in it, as well as the bridge fee, if any.
     #[returns(GetSwapFeeResponse)]
     GetSwapFee {},
 
@@ -52,7 +236,22 @@ pub enum QueryMsg {
     #[returns(IsActiveResponse)]
     IsActive {},
 
+    /// Returns the active status set for the pool and the window it applies in, if any
+    #[returns(ActiveStatusResponse)]
+    GetActiveStatus {},
+
+    /// Returns whether swaps are enabled in each direction between the pool assets, along with
+    /// the denoms paused
+    #[returns(SwapDirectionsResponse)]
+    GetSwapDirections {},
+
     /// GetTotalShares returns the total number of LP shares in the pool
+    #[returns(TotalSharesResponse)]
+    GetTotalShares {},
+
+    /// Returns the denom of the LP shares of the pool, or the LP token address if it's a CW20 token
+    #[returns(ShareDenomResponse)]
+    GetShareDenom {},
 
     /// GetTotalPoolLiquidity returns the coins in the pool owned by all LPs
     #[returns(TotalPoolLiquidityResponse)]
@@ -68,8 +267,18 @@ pub enum QueryMsg {
         base_asset_denom: String,
     },
 
+    /// Same as SpotPrice, but returns the spot price as a Decimal256. It has the same 18 decimal
+    /// places, so it isn't more precise, but its larger range keeps it from overflowing for assets
+    /// with very different decimals or values.
+    #[returns(PreciseSpotPriceResponse)]
+    PreciseSpotPrice {
+        quote_asset_denom: String,
+        base_asset_denom: String,
+    },
+
     /// CalcOutAmtGivenIn calculates the amount of tokenOut given tokenIn and the pool's current state.
     /// Returns error if the given pool is not a CFMM pool. Returns error on internal calculations.
+    /// Returns error if the swap fee doesn't match the one returned by GetSwapFee.
     #[returns(CalcOutAmtGivenInResponse)]
     CalcOutAmtGivenIn {
         token_in: Coin,
@@ -79,6 +288,7 @@ pub enum QueryMsg {
 
     /// CalcInAmtGivenOut calculates the amount of tokenIn given tokenOut and the pool's current state.
     /// Returns error if the given pool is not a CFMM pool. Returns error on internal calculations.
+    /// Returns error if the swap fee doesn't match the one returned by GetSwapFee.
     #[returns(CalcInAmtGivenOutResponse)]
     CalcInAmtGivenOut {
         token_out: Coin,
@@ -86,9 +296,94 @@ pub enum QueryMsg {
         swap_fee: Decimal,
     },
 
+    /// Simulates providing the given tokens as liquidity, returning the LP shares that would be
+    /// received. Either all the pool assets or, for pairs, a single one can be provided.
+    #[returns(SimulateJoinPoolResponse)]
+    SimulateJoinPool { tokens_in: Vec<Coin> },
+
+    /// Simulates withdrawing the liquidity of the given LP shares, returning the tokens that would
+    /// be received
+    #[returns(SimulateExitPoolResponse)]
+    SimulateExitPool { shares_in: Uint128 },
+
+    /// Returns the rate limits of the swaps and the volume they still allow in the current window,
+    /// including the one left for the sender if provided
+    #[returns(RateLimitsResponse)]
+    GetRateLimits { sender: Option<String> },
+
+    /// Returns the number of swaps and the volumes swapped in and out of the pool for each denom,
+    /// along with the fees paid. The swaps of single sided joins are included.
+    #[returns(StatsResponse)]
+    Stats {},
+
+    /// Returns the daily volumes of the denom between the given times
+    #[returns(VolumeHistoryResponse)]
+    VolumeHistory {
+        denom: String,
+        from: Timestamp,
+        to: Timestamp,
+    },
+
+    /// Returns the time weighted average price of the base asset in the quote asset between the
+    /// given times, computed from the spot prices observed by the swaps. If `end_time` isn't
+    /// provided, the TWAP up to the current block is returned. The TWAP can't start more than 48
+    /// hours before the current block.
+    #[returns(TwapResponse)]
+    Twap {
+        quote_asset_denom: String,
+        base_asset_denom: String,
+        start_time: Timestamp,
+        end_time: Option<Timestamp>,
+    },
+
     /// Returns the config of the contract
     #[returns(Config)]
     GetConfig {},
+
+    /// Returns the owner of the contract and the pending owner, if any
+    #[returns(OwnershipResponse)]
+    GetOwnership {},
+
+    /// Returns the CW20 tokens of the pool and the tokenfactory denoms wrapping them
+    #[returns(Cw20DenomsResponse)]
+    GetCw20Denoms {},
+
+    /// Returns the slippage policy applied to swaps, including the per-direction overrides
+    #[returns(SlippageConfigResponse)]
+    GetSlippageConfig {},
+
+    /// Returns how spot prices are computed
+    #[returns(SpotPriceConfig)]
+    GetSpotPriceConfig {},
+
+    /// Returns the bridge fee, the senders exempted from it and the fees accrued so far
+    #[returns(BridgeFeeResponse)]
+    GetBridgeFee {},
+
+    /// Returns how the sender access list is applied to swaps and the senders in it, paginated
+    #[returns(SenderAccessListResponse)]
+    GetSenderAccessList {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the circuit breaker config and the spot prices last observed for each pair swapped
+    #[returns(CircuitBreakerResponse)]
+    GetCircuitBreaker {},
+
+    /// Returns the routes swaps are executed through by the White Whale router
+    #[returns(SwapRoutesResponse)]
+    GetSwapRoutes {},
+
+    /// Returns what migrating the contract to `target_version` would do, without migrating it.
+    /// Returns the same error the migration would if it can't be done.
+    #[returns(MigrationPlanResponse)]
+    MigrationPlan { target_version: String },
+}
+
+#[cw_serde]
+pub struct TwapResponse {
+    pub twap: Decimal256,
 }
 
 #[cw_serde]
@@ -101,6 +396,47 @@ pub struct IsActiveResponse {
     pub is_active: bool,
 }
 
+#[cw_serde]
+pub struct ActiveStatusResponse {
+    /// Whether the pool is active at the moment
+    pub is_active: bool,
+    /// The active status set, which the pool has within the active window
+    pub status: bool,
+    pub active_window: Option<ActiveWindow>,
+}
+
+#[cw_serde]
+pub struct SwapDirectionsResponse {
+    pub is_active: bool,
+    pub directions: Vec<SwapDirection>,
+    pub paused_denoms: Vec<PausedDenom>,
+}
+
+#[cw_serde]
+pub struct SwapDirection {
+    pub token_in_denom: String,
+    pub token_out_denom: String,
+    /// Whether swapping the token in for the token out is enabled, i.e. the pool is active and
+    /// neither direction is paused
+    pub is_enabled: bool,
+}
+
+#[cw_serde]
+pub struct PausedDenom {
+    pub denom: String,
+    pub pause: DenomPause,
+}
+
+#[cw_serde]
+pub struct TotalSharesResponse {
+    pub total_shares: Uint128,
+}
+
+#[cw_serde]
+pub struct ShareDenomResponse {
+    pub share_denom: String,
+}
+
 #[cw_serde]
 pub struct TotalPoolLiquidityResponse {
     pub total_pool_liquidity: Vec<Coin>,
@@ -111,6 +447,11 @@ pub struct SpotPriceResponse {
     pub spot_price: Decimal,
 }
 
+#[cw_serde]
+pub struct PreciseSpotPriceResponse {
+    pub spot_price: Decimal256,
+}
+
 #[cw_serde]
 pub struct CalcOutAmtGivenInResponse {
     pub token_out: Coin,
@@ -122,7 +463,151 @@ pub struct CalcInAmtGivenOutResponse {
 }
 
 #[cw_serde]
-pub struct MigrateMsg {}
+pub struct SimulateJoinPoolResponse {
+    pub shares_out: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulateExitPoolResponse {
+    pub tokens_out: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct Cw20DenomsResponse {
+    pub cw20_denoms: Vec<Cw20Denom>,
+}
+
+#[cw_serde]
+pub struct Cw20Denom {
+    pub contract_addr: String,
+    pub denom: String,
+}
+
+#[cw_serde]
+pub struct SlippageConfigResponse {
+    pub max_spread: Decimal,
+    pub use_belief_price: bool,
+    pub max_spread_overrides: Vec<MaxSpreadOverride>,
+}
+
+#[cw_serde]
+pub struct BridgeFeeResponse {
+    pub bridge_fee: Option<BridgeFee>,
+    pub exemptions: Vec<Addr>,
+    pub accrued_fees: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct SenderAccessListResponse {
+    pub mode: Option<SenderAccessMode>,
+    pub senders: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct CircuitBreakerResponse {
+    pub config: Option<CircuitBreakerConfig>,
+    pub price_observations: Vec<PairPriceObservation>,
+}
+
+#[cw_serde]
+pub struct MigrationPlanResponse {
+    pub stored_version: String,
+    pub target_version: String,
+    /// The migration steps to run, in order
+    pub steps: Vec<MigrationStepInfo>,
+    /// The state keys written or removed by the migration, including the `contract_info` one
+    /// storing the version. A new owner or White Whale pool given in the migrate message also
+    /// updates the `config` key, and the former the `pending_owner` one.
+    pub affected_keys: Vec<String>,
+}
+
+#[cw_serde]
+pub struct MigrationStepInfo {
+    /// The version the step migrates the state to
+    pub version: String,
+    pub description: String,
+    /// The state keys written or removed by the step
+    pub affected_keys: Vec<String>,
+}
+
+#[cw_serde]
+pub struct StatsResponse {
+    pub swap_count: u64,
+    pub volumes: Vec<DenomSwapVolume>,
+}
+
+#[cw_serde]
+pub struct DenomSwapVolume {
+    pub denom: String,
+    pub volume: SwapVolume,
+}
+
+#[cw_serde]
+pub struct VolumeHistoryResponse {
+    pub denom: String,
+    pub history: Vec<DailySwapVolume>,
+}
+
+#[cw_serde]
+pub struct DailySwapVolume {
+    /// The start of the day, in UTC
+    pub day_start: Timestamp,
+    pub volume: SwapVolume,
+}
+
+#[cw_serde]
+pub struct RateLimitsResponse {
+    pub rate_limits: Vec<RateLimitResponse>,
+}
+
+#[cw_serde]
+pub struct RateLimitResponse {
+    pub denom: String,
+    pub rate_limit: RateLimit,
+    pub remaining_volume: Uint128,
+    /// The volume left for the sender queried, if any and if the rate limit caps it
+    pub remaining_sender_volume: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct PairPriceObservation {
+    pub base_denom: String,
+    pub quote_denom: String,
+    pub observation: PriceObservation,
+}
+
+#[cw_serde]
+pub struct SwapRoutesResponse {
+    pub swap_routes: Vec<SwapRoute>,
+}
+
+#[cw_serde]
+pub struct SwapRoute {
+    pub offer_denom: String,
+    pub ask_denom: String,
+    pub operations: Vec<SwapOperation>,
+}
+
+#[cw_serde]
+pub struct OwnershipResponse {
+    pub owner: Addr,
+    pub pending_owner: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {
+    /// The new owner of the contract. Required when migrating from a version without owner.
+    pub owner: Option<String>,
+    /// The new White Whale pool to point the contract to, e.g. when White Whale redeploys it. It
+    /// must hold the same assets as the current pool.
+    pub white_whale_pool: Option<String>,
+    /// The slippage config to set when migrating from a version without it. Defaults to a 30% max
+    /// spread without belief price.
+    pub slippage_config: Option<SlippageConfig>,
+    /// The spot price config to set when migrating from a version without it. Defaults to
+    /// excluding the pool fees.
+    pub spot_price_config: Option<SpotPriceConfig>,
+}
 
 #[cw_serde]
 pub struct AfterPoolCreated {
@@ -139,7 +624,151 @@ pub enum CreatePoolGauges {
 
 #[cw_serde]
 pub struct Config {
+    pub owner: Addr,
     pub white_whale_pool: Addr,
+    /// The type of the White Whale pool, detected at instantiation. Contracts instantiated before
+    /// trios were supported always point to a pair.
+    #[serde(default)]
+    pub pool_type: PoolType,
+    /// The LP token of the White Whale pool, either a CW20 token or a tokenfactory denom
+    pub lp_token: AssetInfo,
+    /// The White Whale router executing the routed swaps, if any
+    #[serde(default)]
+    pub router: Option<Addr>,
+}
+
+/// The type of White Whale pool the contract is bridging
+#[cw_serde]
+#[derive(Default)]
+pub enum PoolType {
+    /// A two-asset pool, either constant product or stableswap
+    #[default]
+    Pair,
+    /// A three-asset stableswap pool
+    Trio,
+}
+
+impl std::fmt::Display for PoolType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PoolType::Pair => write!(f, "pair"),
+            PoolType::Trio => write!(f, "trio"),
+        }
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct SpotPriceConfig {
+    /// If true, the spot price is the marginal rate received after the pool fees are charged
+    pub include_fees: bool,
+}
+
+#[cw_serde]
+pub struct SlippageConfig {
+    /// The default max spread sent to the White Whale pool when swapping
+    pub max_spread: Decimal,
+    /// If true, the belief price sent to the White Whale pool is derived from the pre-swap simulation
+    pub use_belief_price: bool,
+}
+
+impl Default for SlippageConfig {
+    fn default() -> Self {
+        SlippageConfig {
+            max_spread: Decimal::percent(30),
+            use_belief_price: false,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct BridgeFee {
+    /// The share of the amount out charged on swaps
+    pub share: Decimal,
+    /// The address the bridge fees are sent to
+    pub recipient: Addr,
+}
+
+/// The times between which the pool has the active status set, having the opposite one outside
+/// of them. Unset bounds are open.
+#[cw_serde]
+pub struct ActiveWindow {
+    pub from: Option<Timestamp>,
+    pub until: Option<Timestamp>,
+}
+
+/// The swap directions paused for a denom
+#[cw_serde]
+#[derive(Default)]
+pub struct DenomPause {
+    /// If true, the swaps selling the denom, i.e. with it as token in, are paused
+    pub sell: bool,
+    /// If true, the swaps buying the denom, i.e. with it as token out, are paused
+    pub buy: bool,
+}
+
+/// How the sender access list is applied to swaps
+#[cw_serde]
+pub enum SenderAccessMode {
+    /// Only the senders in the list can swap
+    Allowlist,
+    /// The senders in the list can't swap
+    Denylist,
+}
+
+#[cw_serde]
+pub struct CircuitBreakerConfig {
+    /// The maximum change of the spot price a single swap can cause, relative to the price before it
+    pub max_swap_price_change: Decimal,
+    /// The maximum change of the spot price the swaps of a block can cause together, relative to
+    /// the price before the first of them
+    pub max_block_price_change: Decimal,
+}
+
+#[cw_serde]
+pub struct PriceObservation {
+    /// The height of the block of the last swap
+    pub block_height: u64,
+    /// The spot price before the first swap of that block
+    pub block_start_price: Decimal256,
+    /// The spot price after the last swap
+    pub last_price: Decimal256,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct SwapVolume {
+    /// The amount of the denom swapped into the pool
+    pub volume_in: Uint128,
+    /// The amount of the denom swapped out of the pool
+    pub volume_out: Uint128,
+    /// The pool and bridge fees paid in the denom
+    pub fees: Uint128,
+}
+
+#[cw_serde]
+pub struct RateLimit {
+    /// The window the volume is capped over, either in blocks or in seconds
+    pub window: Duration,
+    /// The maximum amount of the denom that can be swapped out of the pool in a window
+    pub max_volume: Uint128,
+    /// The maximum amount of the denom a single sender can swap out of the pool in a window, if any
+    pub max_sender_volume: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct RateLimitUsage {
+    /// The block height or time in seconds the window started at
+    pub window_start: u64,
+    /// The amount swapped out of the pool in the window
+    pub volume: Uint128,
+}
+
+#[cw_serde]
+pub struct MaxSpreadOverride {
+    pub offer_denom: String,
+    pub ask_denom: String,
+    pub max_spread: Decimal,
 }
 
 #[cw_serde]
@@ -155,7 +784,60 @@ pub struct SwapExactAmountOutResponseData {
 #[cw_serde]
 pub struct MinimumReceiveAssertion {
     pub asset_info: AssetInfo,
+    /// The balance of the contract before the swap, as it receives the swapped tokens itself
     pub prev_balance: Uint128,
     pub minimum_receive: Uint128,
+    /// The address the swapped tokens are forwarded to
+    pub recipient: String,
+    /// The share of the swapped tokens charged as bridge fee
+    pub bridge_fee_share: Decimal,
+}
+
+#[cw_serde]
+pub struct ExactAmountOutAssertion {
+    pub asset_info: AssetInfo,
+    /// The balance of the contract before the swap, as it receives the swapped tokens itself
+    pub prev_balance: Uint128,
+    pub token_out_amount: Uint128,
+    /// The address the swapped tokens are forwarded to
+    pub recipient: String,
+    /// The share of the swapped tokens charged as bridge fee
+    pub bridge_fee_share: Decimal,
+}
+
+#[cw_serde]
+pub struct TwapRecord {
+    /// The spot price of the pair since the record was taken
+    pub spot_price: Decimal256,
+    /// The sum of the spot prices the pair had until the record was taken, each weighted by the
+    /// seconds it lasted
+    pub price_accumulator: Decimal256,
+}
+
+#[cw_serde]
+pub struct PreSwapPrice {
+    pub base_denom: String,
+    pub quote_denom: String,
+    /// The spot price of the pair before the swap
+    pub spot_price: Decimal256,
+}
+
+#[cw_serde]
+pub struct SingleSidedJoinData {
+    /// The asset provided along with the swapped one
+    pub offer_asset: Asset,
+    pub ask_asset_info: AssetInfo,
+    /// The ask asset balance of the contract before the swap
+    pub prev_balance: Uint128,
+    pub slippage_tolerance: Option<Decimal>,
+    /// The sender of the join, whose rate limit the swap is counted against
+    pub sender: String,
+    pub receiver: String,
+}
+
+#[cw_serde]
+pub struct ExitPoolData {
+    /// The balances of the pool assets held by the contract before withdrawing the liquidity
+    pub prev_balances: Vec<Asset>,
     pub receiver: String,
 }
@@ -1,28 +1,207 @@
 use cosmwasm_std::{
-    to_json_binary, Coin, Decimal, Deps, QueryRequest, StdError, StdResult, Uint128, WasmQuery,
+    coin, to_json_binary, Addr, BlockInfo, Coin, Decimal, Decimal256, DecimalRangeExceeded, Deps,
+    Env, Order, QueryRequest, StdError, StdResult, Timestamp, Uint128, WasmQuery,
 };
-use white_whale_std::pool_network::asset::{Asset, AssetInfo, ToCoins};
+use cw20::{Cw20QueryMsg, TokenInfoResponse};
+use cw_storage_plus::Bound;
+use cw_utils::Duration;
+use semver::Version;
+use white_whale_std::pool_network::asset::{Asset, AssetInfo, PairInfo, PairType, TrioInfo};
 use white_whale_std::pool_network::pair::{
-    ConfigResponse, PoolResponse, ReverseSimulationResponse, SimulationResponse,
+    PoolFee, PoolResponse, ReverseSimulationResponse, SimulationResponse,
 };
+use white_whale_std::pool_network::router::{SimulateSwapOperationsResponse, SwapOperation};
 
+use crate::helpers::{asset_info_to_denom, assets_to_coins, denom_to_asset_info};
+use crate::migrations;
 use crate::msg::{
-    CalcInAmtGivenOutResponse, CalcOutAmtGivenInResponse, Config, GetSwapFeeResponse,
-    IsActiveResponse, SpotPriceResponse, TotalPoolLiquidityResponse,
+    ActiveStatusResponse, BridgeFeeResponse, CalcInAmtGivenOutResponse, CalcOutAmtGivenInResponse,
+    CircuitBreakerResponse, Config, Cw20Denom, Cw20DenomsResponse, DailySwapVolume,
+    DenomSwapVolume, GetSwapFeeResponse, IsActiveResponse, MaxSpreadOverride,
+    MigrationPlanResponse, MigrationStepInfo, OwnershipResponse, PairPriceObservation, PausedDenom,
+    PoolType, PreciseSpotPriceResponse, RateLimitResponse, RateLimitUsage, RateLimitsResponse,
+    SenderAccessListResponse, ShareDenomResponse, SimulateExitPoolResponse,
+    SimulateJoinPoolResponse, SlippageConfigResponse, SpotPriceConfig, SpotPriceResponse,
+    StatsResponse, SwapDirection, SwapDirectionsResponse, SwapRoute, SwapRoutesResponse,
+    TotalPoolLiquidityResponse, TotalSharesResponse, TwapResponse, VolumeHistoryResponse,
 };
-use crate::state::{CONFIG, IS_ACTIVE};
+use crate::stableswap_math;
+use crate::state::{
+    ACCRUED_BRIDGE_FEES, ACTIVE_WINDOW, BRIDGE_FEE, BRIDGE_FEE_EXEMPTIONS, CIRCUIT_BREAKER_CONFIG,
+    CONFIG, CW20_WRAPPED_DENOMS, DAILY_SWAP_VOLUMES, DENOM_PAUSES, IS_ACTIVE, MAX_SPREAD_OVERRIDES,
+    PENDING_OWNER, PRICE_OBSERVATIONS, RATE_LIMITS, RATE_LIMIT_USAGES, SENDER_ACCESS_LIST,
+    SENDER_ACCESS_MODE, SENDER_RATE_LIMIT_USAGES, SLIPPAGE_CONFIG, SPOT_PRICE_CONFIG, SWAP_COUNT,
+    SWAP_ROUTES, SWAP_VOLUMES, TWAP_RECORDS,
+};
+
+/// The key cw2 stores the contract name and version under
+const CONTRACT_INFO_KEY: &str = "contract_info";
+
+/// Maximum number of times the offer amount computed by the reverse simulation is topped up to
+/// cover the rounding shortfall
+const MAX_OFFER_AMOUNT_ADJUSTMENTS: u8 = 3;
+
+pub(crate) const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Maximum number of seconds in the past a TWAP can start, older records being pruned
+pub(crate) const MAX_TWAP_PERIOD: u64 = 2 * SECONDS_PER_DAY;
+
+/// Default and maximum number of items returned by the paginated queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// Maximum number of days the volume history can be queried for at once
+const MAX_VOLUME_HISTORY_DAYS: u64 = 366;
+
+/// The info of the White Whale pool, regardless of its type
+pub(crate) struct PoolInfo {
+    pub asset_infos: Vec<AssetInfo>,
+    pub asset_decimals: Vec<u8>,
+    pub pair_type: PairType,
+    pub liquidity_token: AssetInfo,
+}
 
-/// Queries the pool config
-fn get_pool_config(deps: Deps) -> StdResult<ConfigResponse> {
+/// Detects the type of the given White Whale pool, i.e. whether it's a pair or a trio
+pub(crate) fn detect_pool_type(deps: Deps, pool: &Addr) -> StdResult<PoolType> {
+    let pair_info: StdResult<PairInfo> =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: pool.to_string(),
+            msg: to_json_binary(&white_whale_std::pool_network::pair::QueryMsg::Pair {})?,
+        }));
+    if pair_info.is_ok() {
+        return Ok(PoolType::Pair);
+    }
+
+    let trio_info: StdResult<TrioInfo> =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: pool.to_string(),
+            msg: to_json_binary(&white_whale_std::pool_network::trio::QueryMsg::Trio {})?,
+        }));
+    if trio_info.is_ok() {
+        return Ok(PoolType::Trio);
+    }
+
+    Err(StdError::generic_err(format!(
+        "{} is not a White Whale pair nor trio",
+        pool
+    )))
+}
+
+/// Queries the info of the White Whale pool
+pub(crate) fn get_pool_info(deps: Deps, env: &Env) -> StdResult<PoolInfo> {
     let config = CONFIG.load(deps.storage)?;
 
+    query_pool_info(deps, env, &config.white_whale_pool, &config.pool_type)
+}
+
+/// Queries the info of the given White Whale pool of the given type
+pub(crate) fn query_pool_info(
+    deps: Deps,
+    env: &Env,
+    pool: &Addr,
+    pool_type: &PoolType,
+) -> StdResult<PoolInfo> {
+    match pool_type {
+        PoolType::Pair => {
+            let pair_info: PairInfo =
+                deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                    contract_addr: pool.to_string(),
+                    msg: to_json_binary(&white_whale_std::pool_network::pair::QueryMsg::Pair {})?,
+                }))?;
+
+            Ok(PoolInfo {
+                asset_infos: pair_info.asset_infos.to_vec(),
+                asset_decimals: pair_info.asset_decimals.to_vec(),
+                pair_type: pair_info.pair_type,
+                liquidity_token: pair_info.liquidity_token,
+            })
+        }
+        PoolType::Trio => {
+            let trio_info: TrioInfo =
+                deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                    contract_addr: pool.to_string(),
+                    msg: to_json_binary(&white_whale_std::pool_network::trio::QueryMsg::Trio {})?,
+                }))?;
+            let trio_config = get_trio_config(deps, pool)?;
+
+            // trios are always stableswap pools
+            Ok(PoolInfo {
+                asset_infos: trio_info.asset_infos.to_vec(),
+                asset_decimals: trio_info.asset_decimals.to_vec(),
+                pair_type: PairType::StableSwap {
+                    amp: get_trio_amp(&trio_config, env.block.height),
+                },
+                liquidity_token: trio_info.liquidity_token,
+            })
+        }
+    }
+}
+
+/// Queries the config of the White Whale trio
+fn get_trio_config(
+    deps: Deps,
+    pool: &Addr,
+) -> StdResult<white_whale_std::pool_network::trio::ConfigResponse> {
     deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: config.white_whale_pool.to_string(),
-        msg: to_json_binary(&white_whale_std::pool_network::pair::QueryMsg::Config {})?,
+        contract_addr: pool.to_string(),
+        msg: to_json_binary(&white_whale_std::pool_network::trio::QueryMsg::Config {})?,
     }))
 }
 
-/// Queries the pool data
+/// Computes the current amplification of a trio, which ramps linearly from the initial to the
+/// future amplification between the initial and future amp blocks
+fn get_trio_amp(
+    trio_config: &white_whale_std::pool_network::trio::ConfigResponse,
+    block_height: u64,
+) -> u64 {
+    let (initial_amp, future_amp) = (trio_config.initial_amp, trio_config.future_amp);
+    let (initial_block, future_block) =
+        (trio_config.initial_amp_block, trio_config.future_amp_block);
+
+    if block_height >= future_block || future_block <= initial_block {
+        return future_amp;
+    }
+
+    let elapsed_blocks = block_height.saturating_sub(initial_block) as u128;
+    let ramp_blocks = (future_block - initial_block) as u128;
+    let amp_delta =
+        (initial_amp.abs_diff(future_amp) as u128 * elapsed_blocks / ramp_blocks) as u64;
+
+    if future_amp >= initial_amp {
+        initial_amp + amp_delta
+    } else {
+        initial_amp - amp_delta
+    }
+}
+
+/// Queries the fees of the White Whale pool
+fn get_pool_fees(deps: Deps) -> StdResult<PoolFee> {
+    let config = CONFIG.load(deps.storage)?;
+
+    match config.pool_type {
+        PoolType::Pair => {
+            let pair_config: white_whale_std::pool_network::pair::ConfigResponse =
+                deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                    contract_addr: config.white_whale_pool.to_string(),
+                    msg: to_json_binary(&white_whale_std::pool_network::pair::QueryMsg::Config {})?,
+                }))?;
+
+            Ok(pair_config.pool_fees)
+        }
+        PoolType::Trio => {
+            let pool_fees = get_trio_config(deps, &config.white_whale_pool)?.pool_fees;
+
+            Ok(PoolFee {
+                protocol_fee: pool_fees.protocol_fee,
+                swap_fee: pool_fees.swap_fee,
+                burn_fee: pool_fees.burn_fee,
+                osmosis_fee: pool_fees.osmosis_fee,
+            })
+        }
+    }
+}
+
+/// Queries the pool data. The pool query is the same for pairs and trios.
 fn get_pool(deps: Deps) -> StdResult<PoolResponse> {
     let config = CONFIG.load(deps.storage)?;
 
@@ -33,153 +212,1034 @@ fn get_pool(deps: Deps) -> StdResult<PoolResponse> {
 }
 
 /// Finds the amount of tokens in a vector of Assets by denom
-fn find_asset_amount_by_denom(assets: &[Asset], denom: &str) -> Option<Uint128> {
-    assets
+fn find_asset_amount_by_denom(
+    deps: Deps,
+    assets: &[Asset],
+    denom: &str,
+) -> StdResult<Option<Uint128>> {
+    let asset_info = denom_to_asset_info(deps, denom)?;
+
+    Ok(assets
         .iter()
-        .find(|&asset| match asset.clone().info {
-            AssetInfo::Token { .. } => false,
-            AssetInfo::NativeToken { denom: asset_denom } => asset_denom == denom,
-        })
-        .map(|asset| asset.amount)
+        .find(|&asset| asset.info == asset_info)
+        .map(|asset| asset.amount))
 }
 
 /// Queries if the pool is active or not
-pub(crate) fn is_active(deps: Deps) -> StdResult<IsActiveResponse> {
-    let is_active = IS_ACTIVE.load(deps.storage)?;
+pub(crate) fn is_active(deps: Deps, env: Env) -> StdResult<IsActiveResponse> {
+    let is_active = is_pool_active(deps, env.block.time)?;
 
     Ok(IsActiveResponse { is_active })
 }
 
-/// Queries the swap fee
+/// Queries the active status set for the pool and the window it applies in
+pub(crate) fn get_active_status(deps: Deps, env: Env) -> StdResult<ActiveStatusResponse> {
+    Ok(ActiveStatusResponse {
+        is_active: is_pool_active(deps, env.block.time)?,
+        status: IS_ACTIVE.load(deps.storage)?,
+        active_window: ACTIVE_WINDOW.may_load(deps.storage)?,
+    })
+}
+
+/// Checks whether the pool is active at the given time. The active status set applies within the
+/// active window, if any, and the opposite one outside of it.
+pub(crate) fn is_pool_active(deps: Deps, time: Timestamp) -> StdResult<bool> {
+    let status = IS_ACTIVE.load(deps.storage)?;
+    let is_within_window = match ACTIVE_WINDOW.may_load(deps.storage)? {
+        Some(window) => {
+            let is_before_start = window.from.is_some_and(|from| time < from);
+            let is_after_end = window.until.is_some_and(|until| time >= until);
+            !is_before_start && !is_after_end
+        }
+        None => true,
+    };
+
+    Ok(status == is_within_window)
+}
+
+/// Queries whether swaps are enabled in each direction between the pool assets, and the denoms
+/// paused
+pub(crate) fn get_swap_directions(deps: Deps, env: Env) -> StdResult<SwapDirectionsResponse> {
+    let is_active = is_pool_active(deps, env.block.time)?;
+    let denoms = get_pool_info(deps, &env)?
+        .asset_infos
+        .iter()
+        .map(|asset_info| asset_info_to_denom(deps, asset_info))
+        .collect::<StdResult<Vec<String>>>()?;
+
+    let mut directions = vec![];
+    for token_in_denom in &denoms {
+        for token_out_denom in denoms.iter().filter(|denom| *denom != token_in_denom) {
+            directions.push(SwapDirection {
+                token_in_denom: token_in_denom.clone(),
+                token_out_denom: token_out_denom.clone(),
+                is_enabled: is_active
+                    && !is_swap_direction_paused(deps, token_in_denom, token_out_denom)?,
+            });
+        }
+    }
+
+    let paused_denoms = DENOM_PAUSES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, pause) = item?;
+            Ok(PausedDenom { denom, pause })
+        })
+        .collect::<StdResult<Vec<PausedDenom>>>()?;
+
+    Ok(SwapDirectionsResponse {
+        is_active,
+        directions,
+        paused_denoms,
+    })
+}
+
+/// Checks whether selling the token in or buying the token out is paused
+pub(crate) fn is_swap_direction_paused(
+    deps: Deps,
+    token_in_denom: &str,
+    token_out_denom: &str,
+) -> StdResult<bool> {
+    let token_in_pause = DENOM_PAUSES.may_load(deps.storage, token_in_denom)?;
+    let token_out_pause = DENOM_PAUSES.may_load(deps.storage, token_out_denom)?;
+
+    Ok(token_in_pause.is_some_and(|pause| pause.sell)
+        || token_out_pause.is_some_and(|pause| pause.buy))
+}
+
+/// Queries the swap fee, i.e. the share of the amount out charged by the White Whale pool, all its
+/// fees included, plus the bridge fee
 pub(crate) fn get_swap_fee(deps: Deps) -> StdResult<GetSwapFeeResponse> {
-    let fees = get_pool_config(deps)?.pool_fees;
+    let pool_fee_share = get_pool_fee_share(&get_pool_fees(deps)?)?;
 
     Ok(GetSwapFeeResponse {
-        swap_fee: fees.swap_fee.share,
+        swap_fee: pool_fee_share.checked_add(get_bridge_fee_share(deps, None)?)?,
     })
 }
 
+/// Gets the share of the amount out charged by the White Whale pool. The pools charge all their
+/// fees on the swapped amount, not only the swap fee going to the liquidity providers.
+fn get_pool_fee_share(fees: &PoolFee) -> StdResult<Decimal> {
+    Ok(fees
+        .protocol_fee
+        .share
+        .checked_add(fees.swap_fee.share)?
+        .checked_add(fees.burn_fee.share)?
+        .checked_add(fees.osmosis_fee.share)?)
+}
+
+/// Gets the share of the amount out charged as bridge fee to the given sender. Exempted senders
+/// aren't charged, while swaps without sender, i.e. quotes, are.
+pub(crate) fn get_bridge_fee_share(deps: Deps, sender: Option<&Addr>) -> StdResult<Decimal> {
+    if let Some(sender) = sender {
+        if BRIDGE_FEE_EXEMPTIONS.has(deps.storage, sender) {
+            return Ok(Decimal::zero());
+        }
+    }
+
+    Ok(BRIDGE_FEE
+        .may_load(deps.storage)?
+        .map_or(Decimal::zero(), |bridge_fee| bridge_fee.share))
+}
+
+/// Gets the amount out the pool must return so it covers the given net amount after the bridge
+/// fee is deducted from it
+fn gross_amount_out(net_amount: Uint128, bridge_fee_share: Decimal) -> StdResult<Uint128> {
+    let net_share = Decimal::one().checked_sub(bridge_fee_share)?;
+    let mut gross_amount = net_amount
+        .checked_multiply_ratio(Decimal::one().atomics(), net_share.atomics())
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    // the bridge fee is rounded down, so the gross amount is topped up by the rounding dust
+    while gross_amount.checked_sub(gross_amount * bridge_fee_share)? < net_amount {
+        gross_amount = gross_amount.checked_add(Uint128::one())?;
+    }
+
+    Ok(gross_amount)
+}
+
+/// Queries the bridge fee, the senders exempted from it and the fees accrued so far
+pub(crate) fn get_bridge_fee(deps: Deps) -> StdResult<BridgeFeeResponse> {
+    let exemptions = BRIDGE_FEE_EXEMPTIONS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+
+    let accrued_fees = ACCRUED_BRIDGE_FEES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin { denom, amount })
+        })
+        .collect::<StdResult<Vec<Coin>>>()?;
+
+    Ok(BridgeFeeResponse {
+        bridge_fee: BRIDGE_FEE.may_load(deps.storage)?,
+        exemptions,
+        accrued_fees,
+    })
+}
+
+/// Queries how the sender access list is applied to swaps and the senders in it, paginated
+pub(crate) fn get_sender_access_list(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<SenderAccessListResponse> {
+    let start_after = start_after
+        .map(|sender| deps.api.addr_validate(&sender))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let senders = SENDER_ACCESS_LIST
+        .keys(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<Addr>>>()?;
+
+    Ok(SenderAccessListResponse {
+        mode: SENDER_ACCESS_MODE.may_load(deps.storage)?,
+        senders,
+    })
+}
+
+/// Queries the circuit breaker config and the spot prices last observed for each pair swapped
+pub(crate) fn get_circuit_breaker(deps: Deps) -> StdResult<CircuitBreakerResponse> {
+    let price_observations = PRICE_OBSERVATIONS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let ((base_denom, quote_denom), observation) = item?;
+            Ok(PairPriceObservation {
+                base_denom,
+                quote_denom,
+                observation,
+            })
+        })
+        .collect::<StdResult<Vec<PairPriceObservation>>>()?;
+
+    Ok(CircuitBreakerResponse {
+        config: CIRCUIT_BREAKER_CONFIG.may_load(deps.storage)?,
+        price_observations,
+    })
+}
+
+/// Queries the number of swaps and the volumes swapped for each denom
+pub(crate) fn get_stats(deps: Deps) -> StdResult<StatsResponse> {
+    let volumes = SWAP_VOLUMES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, volume) = item?;
+            Ok(DenomSwapVolume { denom, volume })
+        })
+        .collect::<StdResult<Vec<DenomSwapVolume>>>()?;
+
+    Ok(StatsResponse {
+        swap_count: SWAP_COUNT.may_load(deps.storage)?.unwrap_or_default(),
+        volumes,
+    })
+}
+
+/// Queries the daily volumes of the denom between the given times, for at most
+/// [MAX_VOLUME_HISTORY_DAYS] days
+pub(crate) fn get_volume_history(
+    deps: Deps,
+    denom: String,
+    from: Timestamp,
+    to: Timestamp,
+) -> StdResult<VolumeHistoryResponse> {
+    let from_day = from.seconds() / SECONDS_PER_DAY;
+    let to_day = to.seconds() / SECONDS_PER_DAY;
+
+    if from_day > to_day || to_day - from_day >= MAX_VOLUME_HISTORY_DAYS {
+        return Err(StdError::generic_err(format!(
+            "Invalid volume history period, it must end after it starts and span at most {} days",
+            MAX_VOLUME_HISTORY_DAYS
+        )));
+    }
+
+    let history = DAILY_SWAP_VOLUMES
+        .prefix(&denom)
+        .range(
+            deps.storage,
+            Some(Bound::inclusive(from_day)),
+            Some(Bound::inclusive(to_day)),
+            Order::Ascending,
+        )
+        .map(|item| {
+            let (day, volume) = item?;
+            Ok(DailySwapVolume {
+                day_start: Timestamp::from_seconds(day * SECONDS_PER_DAY),
+                volume,
+            })
+        })
+        .collect::<StdResult<Vec<DailySwapVolume>>>()?;
+
+    Ok(VolumeHistoryResponse { denom, history })
+}
+
+/// Queries the rate limits of the swaps and the volume they still allow in the current window
+pub(crate) fn get_rate_limits(
+    deps: Deps,
+    env: Env,
+    sender: Option<String>,
+) -> StdResult<RateLimitsResponse> {
+    let sender = sender
+        .map(|sender| deps.api.addr_validate(&sender))
+        .transpose()?;
+
+    let rate_limits = RATE_LIMITS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, rate_limit) = item?;
+            let window_start = get_rate_limit_window_start(&rate_limit.window, &env.block);
+
+            let volume = get_window_volume(
+                RATE_LIMIT_USAGES.may_load(deps.storage, &denom)?,
+                window_start,
+            );
+
+            let remaining_sender_volume = match (&sender, rate_limit.max_sender_volume) {
+                (Some(sender), Some(max_sender_volume)) => {
+                    let sender_volume = get_window_volume(
+                        SENDER_RATE_LIMIT_USAGES
+                            .may_load(deps.storage, (denom.as_str(), sender.as_str()))?,
+                        window_start,
+                    );
+                    Some(max_sender_volume.saturating_sub(sender_volume))
+                }
+                _ => None,
+            };
+
+            Ok(RateLimitResponse {
+                remaining_volume: rate_limit.max_volume.saturating_sub(volume),
+                remaining_sender_volume,
+                denom,
+                rate_limit,
+            })
+        })
+        .collect::<StdResult<Vec<RateLimitResponse>>>()?;
+
+    Ok(RateLimitsResponse { rate_limits })
+}
+
+/// Gets the block height or time in seconds the window of the rate limit containing the current
+/// block started at
+pub(crate) fn get_rate_limit_window_start(window: &Duration, block: &BlockInfo) -> u64 {
+    match window {
+        Duration::Height(blocks) => block.height - block.height % blocks,
+        Duration::Time(seconds) => {
+            let now = block.time.seconds();
+            now - now % seconds
+        }
+    }
+}
+
+/// Gets the volume swapped in the window starting at `window_start`, which is zero if the usage
+/// recorded belongs to a previous window
+pub(crate) fn get_window_volume(usage: Option<RateLimitUsage>, window_start: u64) -> Uint128 {
+    usage
+        .filter(|usage| usage.window_start == window_start)
+        .map_or(Uint128::zero(), |usage| usage.volume)
+}
+
 /// Queries the total pool liquidity
 pub(crate) fn get_total_pool_liquidity(deps: Deps) -> StdResult<TotalPoolLiquidityResponse> {
     let pool = get_pool(deps)?;
 
     Ok(TotalPoolLiquidityResponse {
-        total_pool_liquidity: pool.assets.to_coins()?,
+        total_pool_liquidity: assets_to_coins(deps, &pool.assets)?,
     })
 }
 
+/// Queries the total number of LP shares in the pool, i.e. the supply of the White Whale pool's
+/// LP token, which is either a CW20 token or a tokenfactory denom
+pub(crate) fn get_total_shares(deps: Deps) -> StdResult<TotalSharesResponse> {
+    let total_shares = match CONFIG.load(deps.storage)?.lp_token {
+        AssetInfo::Token { contract_addr } => {
+            let token_info: TokenInfoResponse =
+                deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                    contract_addr,
+                    msg: to_json_binary(&Cw20QueryMsg::TokenInfo {})?,
+                }))?;
+
+            token_info.total_supply
+        }
+        AssetInfo::NativeToken { denom } => deps.querier.query_supply(denom)?.amount,
+    };
+
+    Ok(TotalSharesResponse { total_shares })
+}
+
+/// Queries the denom of the LP shares of the pool. For CW20 LP tokens it's the token address.
+pub(crate) fn get_share_denom(deps: Deps) -> StdResult<ShareDenomResponse> {
+    Ok(ShareDenomResponse {
+        share_denom: CONFIG.load(deps.storage)?.lp_token.to_string(),
+    })
+}
+
+/// Simulates providing the given tokens as liquidity. The LP shares are minted pro rata to the pool
+/// reserves, capped by the scarcest asset provided. Single sided joins simulate swapping half of
+/// the tokens for the other asset of the pair first.
+pub(crate) fn simulate_join_pool(
+    deps: Deps,
+    env: Env,
+    tokens_in: Vec<Coin>,
+) -> StdResult<SimulateJoinPoolResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pool_info = get_pool_info(deps, &env)?;
+    let pool = get_pool(deps)?;
+
+    if pool.total_share.is_zero() {
+        return Err(StdError::generic_err("The pool has no liquidity"));
+    }
+
+    let mut reserves = pool.assets;
+    let mut assets = tokens_in
+        .iter()
+        .map(|coin| {
+            Ok(Asset {
+                info: denom_to_asset_info(deps, &coin.denom)?,
+                amount: coin.amount,
+            })
+        })
+        .collect::<StdResult<Vec<Asset>>>()?;
+
+    if assets.len() == 1 && config.pool_type == PoolType::Pair {
+        let offer_asset_info = assets[0].info.clone();
+        let ask_asset_info = pool_info
+            .asset_infos
+            .iter()
+            .find(|asset_info| **asset_info != offer_asset_info)
+            .cloned()
+            .ok_or_else(|| StdError::generic_err("The pool has a single asset"))?;
+
+        let swap_amount = assets[0].amount.multiply_ratio(1u128, 2u128);
+        let simulation = simulate_swap(
+            deps,
+            coin(swap_amount.u128(), tokens_in[0].denom.clone()),
+            &asset_info_to_denom(deps, &ask_asset_info)?,
+        )?;
+
+        // the swap fee stays in the pool, while the rest of the fees leave it
+        let ask_amount_out = simulation
+            .return_amount
+            .checked_add(simulation.protocol_fee_amount)?
+            .checked_add(simulation.burn_fee_amount)?
+            .checked_add(simulation.osmosis_fee_amount)?;
+
+        for reserve in reserves.iter_mut() {
+            if reserve.info == offer_asset_info {
+                reserve.amount = reserve.amount.checked_add(swap_amount)?;
+            } else if reserve.info == ask_asset_info {
+                reserve.amount = reserve.amount.checked_sub(ask_amount_out)?;
+            }
+        }
+
+        assets[0].amount = assets[0].amount.checked_sub(swap_amount)?;
+        assets.push(Asset {
+            info: ask_asset_info,
+            amount: simulation.return_amount,
+        });
+    }
+
+    if assets.len() != pool_info.asset_infos.len() {
+        return Err(StdError::generic_err(
+            "Either all the pool assets or, for pairs, a single one must be provided",
+        ));
+    }
+
+    let shares_out = reserves
+        .iter()
+        .map(|reserve| {
+            let amount = assets
+                .iter()
+                .find(|asset| asset.info == reserve.info)
+                .map(|asset| asset.amount)
+                .ok_or_else(|| {
+                    StdError::generic_err(format!("Asset {} not provided", reserve.info))
+                })?;
+
+            Ok(amount.multiply_ratio(pool.total_share, reserve.amount))
+        })
+        .collect::<StdResult<Vec<Uint128>>>()?
+        .into_iter()
+        .min()
+        .unwrap_or_default();
+
+    Ok(SimulateJoinPoolResponse { shares_out })
+}
+
+/// Simulates withdrawing the liquidity of the given LP shares, which is a pro rata share of the
+/// pool reserves
+pub(crate) fn simulate_exit_pool(
+    deps: Deps,
+    shares_in: Uint128,
+) -> StdResult<SimulateExitPoolResponse> {
+    let pool = get_pool(deps)?;
+
+    if shares_in > pool.total_share {
+        return Err(StdError::generic_err(format!(
+            "Can't withdraw {} shares, the pool has {}",
+            shares_in, pool.total_share
+        )));
+    }
+
+    let tokens_out = pool
+        .assets
+        .iter()
+        .map(|asset| {
+            Ok(Coin {
+                denom: asset_info_to_denom(deps, &asset.info)?,
+                amount: asset.amount.multiply_ratio(shares_in, pool.total_share),
+            })
+        })
+        .collect::<StdResult<Vec<Coin>>>()?;
+
+    Ok(SimulateExitPoolResponse { tokens_out })
+}
+
+/// Asserts the swap fee provided by Osmosis matches all the fees charged by the White Whale pool
+/// and the bridge, so quotes and swaps are never served under a fee assumption the pool doesn't
+/// honor.
+fn assert_swap_fee(deps: Deps, swap_fee: Decimal) -> StdResult<()> {
+    let pool_swap_fee = get_swap_fee(deps)?.swap_fee;
+
+    if swap_fee != pool_swap_fee {
+        return Err(StdError::generic_err(format!(
+            "Swap fee mismatch. Provided swap fee: {}, pool swap fee: {}",
+            swap_fee, pool_swap_fee
+        )));
+    }
+
+    Ok(())
+}
+
 /// Queries the config of the contract
 pub(crate) fn get_config(deps: Deps) -> StdResult<Config> {
     CONFIG.load(deps.storage)
 }
 
-/// Queries the spot price
+/// Queries the owner of the contract and the pending owner, if any
+pub(crate) fn get_ownership(deps: Deps) -> StdResult<OwnershipResponse> {
+    Ok(OwnershipResponse {
+        owner: CONFIG.load(deps.storage)?.owner,
+        pending_owner: PENDING_OWNER.may_load(deps.storage)?,
+    })
+}
+
+/// Queries the slippage config, including the per-direction max spread overrides
+pub(crate) fn get_slippage_config(deps: Deps) -> StdResult<SlippageConfigResponse> {
+    let slippage_config = SLIPPAGE_CONFIG.load(deps.storage)?;
+
+    let max_spread_overrides = MAX_SPREAD_OVERRIDES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let ((offer_denom, ask_denom), max_spread) = item?;
+            Ok(MaxSpreadOverride {
+                offer_denom,
+                ask_denom,
+                max_spread,
+            })
+        })
+        .collect::<StdResult<Vec<MaxSpreadOverride>>>()?;
+
+    Ok(SlippageConfigResponse {
+        max_spread: slippage_config.max_spread,
+        use_belief_price: slippage_config.use_belief_price,
+        max_spread_overrides,
+    })
+}
+
+/// Queries how spot prices are computed
+pub(crate) fn get_spot_price_config(deps: Deps) -> StdResult<SpotPriceConfig> {
+    SPOT_PRICE_CONFIG.load(deps.storage)
+}
+
+/// Gets the max spread to use when swapping from `offer_denom` to `ask_denom`, falling back to
+/// the default one if there's no override for the given direction
+pub(crate) fn get_max_spread(deps: Deps, offer_denom: &str, ask_denom: &str) -> StdResult<Decimal> {
+    match MAX_SPREAD_OVERRIDES.may_load(deps.storage, (offer_denom, ask_denom))? {
+        Some(max_spread) => Ok(max_spread),
+        None => Ok(SLIPPAGE_CONFIG.load(deps.storage)?.max_spread),
+    }
+}
+
+/// Queries the spot price of the base asset in terms of the quote asset
 pub(crate) fn spot_price(
     deps: Deps,
+    env: Env,
     quote_asset_denom: String,
     base_asset_denom: String,
 ) -> StdResult<SpotPriceResponse> {
+    let spot_price = compute_spot_price(deps, &env, &quote_asset_denom, &base_asset_denom)?
+        .try_into()
+        .map_err(|err: DecimalRangeExceeded| StdError::generic_err(err.to_string()))?;
+
+    Ok(SpotPriceResponse { spot_price })
+}
+
+/// Queries the spot price of the base asset in terms of the quote asset as a Decimal256, which
+/// doesn't overflow when the price exceeds the range of a Decimal
+pub(crate) fn precise_spot_price(
+    deps: Deps,
+    env: Env,
+    quote_asset_denom: String,
+    base_asset_denom: String,
+) -> StdResult<PreciseSpotPriceResponse> {
+    Ok(PreciseSpotPriceResponse {
+        spot_price: compute_spot_price(deps, &env, &quote_asset_denom, &base_asset_denom)?,
+    })
+}
+
+/// Computes the spot price, i.e. the marginal rate at which the base asset is swapped for the
+/// quote asset. For constant product pools it's the ratio of the reserves, while for stableswap
+/// pools it's derived from the pool's invariant. If the spot price config says so, the pool fees
+/// charged on the swapped amount are deducted from it.
+pub(crate) fn compute_spot_price(
+    deps: Deps,
+    env: &Env,
+    quote_asset_denom: &str,
+    base_asset_denom: &str,
+) -> StdResult<Decimal256> {
+    Ok(compute_spot_prices(deps, env, quote_asset_denom, base_asset_denom)?.0)
+}
+
+/// Computes the spot price of the base asset in the quote asset, along with the one of the quote
+/// asset in the base asset, out of a single read of the pool.
+pub(crate) fn compute_spot_prices(
+    deps: Deps,
+    env: &Env,
+    quote_asset_denom: &str,
+    base_asset_denom: &str,
+) -> StdResult<(Decimal256, Decimal256)> {
+    let pool_info = get_pool_info(deps, env)?;
     let pool = get_pool(deps)?;
 
-    let quote_asset_amount = find_asset_amount_by_denom(&pool.assets, &quote_asset_denom)
+    let quote_asset_amount = find_asset_amount_by_denom(deps, &pool.assets, quote_asset_denom)?
         .ok_or_else(|| StdError::generic_err("Quote asset not found"))?;
 
-    let base_asset_amount = find_asset_amount_by_denom(&pool.assets, &base_asset_denom)
+    let base_asset_amount = find_asset_amount_by_denom(deps, &pool.assets, base_asset_denom)?
         .ok_or_else(|| StdError::generic_err("Base asset not found"))?;
 
-    Ok(SpotPriceResponse {
-        spot_price: Decimal::from_ratio(quote_asset_amount, base_asset_amount),
-    })
+    let (spot_price, inverse_spot_price) = match pool_info.pair_type {
+        PairType::ConstantProduct => {
+            let ratio = |numerator: Uint128, denominator: Uint128| {
+                Decimal256::checked_from_ratio(numerator, denominator)
+                    .map_err(|err| StdError::generic_err(err.to_string()))
+            };
+            (
+                ratio(quote_asset_amount, base_asset_amount)?,
+                ratio(base_asset_amount, quote_asset_amount)?,
+            )
+        }
+        PairType::StableSwap { amp } => {
+            let reserves = pool_info
+                .asset_infos
+                .iter()
+                .map(|asset_info| {
+                    pool.assets
+                        .iter()
+                        .find(|asset| asset.info == *asset_info)
+                        .map(|asset| asset.amount)
+                        .ok_or_else(|| {
+                            StdError::generic_err(format!("Asset {} not found", asset_info))
+                        })
+                })
+                .collect::<StdResult<Vec<Uint128>>>()?;
+
+            let base_index =
+                find_asset_index(&pool_info, denom_to_asset_info(deps, base_asset_denom)?)?;
+            let quote_index =
+                find_asset_index(&pool_info, denom_to_asset_info(deps, quote_asset_denom)?)?;
+
+            stableswap_math::spot_prices(
+                amp,
+                &reserves,
+                &pool_info.asset_decimals,
+                base_index,
+                quote_index,
+            )?
+        }
+    };
+
+    if !SPOT_PRICE_CONFIG.load(deps.storage)?.include_fees {
+        return Ok((spot_price, inverse_spot_price));
+    }
+
+    let fee_share = get_pool_fee_share(&get_pool_fees(deps)?)?;
+    let fee_factor = Decimal256::one().checked_sub(fee_share.into())?;
+
+    Ok((
+        spot_price.checked_mul(fee_factor)?,
+        inverse_spot_price.checked_mul(fee_factor)?,
+    ))
+}
+
+/// Finds the index of the given asset in the pool
+fn find_asset_index(pool_info: &PoolInfo, asset_info: AssetInfo) -> StdResult<usize> {
+    pool_info
+        .asset_infos
+        .iter()
+        .position(|info| *info == asset_info)
+        .ok_or_else(|| StdError::generic_err(format!("Asset {} not found", asset_info)))
+}
+
+/// Queries the time weighted average price of the base asset in the quote asset between the given
+/// times, from the spot prices recorded by the swaps.
+pub(crate) fn get_twap(
+    deps: Deps,
+    env: Env,
+    quote_asset_denom: String,
+    base_asset_denom: String,
+    start_time: Timestamp,
+    end_time: Option<Timestamp>,
+) -> StdResult<TwapResponse> {
+    let start_time = start_time.seconds();
+    let end_time = end_time.unwrap_or(env.block.time).seconds();
+
+    if start_time >= end_time || end_time > env.block.time.seconds() {
+        return Err(StdError::generic_err(
+            "Invalid TWAP period, it must end after it starts and not in the future",
+        ));
+    }
+    if start_time < env.block.time.seconds().saturating_sub(MAX_TWAP_PERIOD) {
+        return Err(StdError::generic_err(format!(
+            "Invalid TWAP period, it can't start more than {} seconds ago",
+            MAX_TWAP_PERIOD
+        )));
+    }
+
+    let price_accumulator = |time: u64| {
+        get_price_accumulator(deps, &base_asset_denom, &quote_asset_denom, time)?.ok_or_else(|| {
+            StdError::generic_err(format!(
+                "No price of {} in {} recorded before {}",
+                base_asset_denom, quote_asset_denom, time
+            ))
+        })
+    };
+
+    let twap = price_accumulator(end_time)?
+        .checked_sub(price_accumulator(start_time)?)?
+        .checked_div(Decimal256::from_ratio(end_time - start_time, 1u64))
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    Ok(TwapResponse { twap })
+}
+
+/// Gets the price accumulator of the pair at the given time in seconds, extrapolated from the
+/// last record taken by then. Returns `None` if the pair had no record by then.
+pub(crate) fn get_price_accumulator(
+    deps: Deps,
+    base_denom: &str,
+    quote_denom: &str,
+    time: u64,
+) -> StdResult<Option<Decimal256>> {
+    TWAP_RECORDS
+        .prefix((base_denom, quote_denom))
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::inclusive(time)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?
+        .map(|(record_time, record)| {
+            let elapsed = Decimal256::from_ratio(time - record_time, 1u64);
+            Ok(record
+                .price_accumulator
+                .checked_add(record.spot_price.checked_mul(elapsed)?)?)
+        })
+        .transpose()
+}
+
+/// Simulates a swap on the White Whale pool
+pub(crate) fn simulate_swap(
+    deps: Deps,
+    offer: Coin,
+    ask_denom: &str,
+) -> StdResult<SimulationResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let offer_asset = Asset {
+        info: denom_to_asset_info(deps, &offer.denom)?,
+        amount: offer.amount,
+    };
+
+    let msg = match config.pool_type {
+        PoolType::Pair => {
+            to_json_binary(&white_whale_std::pool_network::pair::QueryMsg::Simulation {
+                offer_asset,
+            })?
+        }
+        PoolType::Trio => {
+            to_json_binary(&white_whale_std::pool_network::trio::QueryMsg::Simulation {
+                offer_asset,
+                ask_asset: Asset {
+                    info: denom_to_asset_info(deps, ask_denom)?,
+                    amount: Uint128::zero(),
+                },
+            })?
+        }
+    };
+
+    // the simulation responses are the same for pairs and trios
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: config.white_whale_pool.to_string(),
+        msg,
+    }))
 }
 
 /// CalcOutAmtGivenIn calculates the amount of tokenOut given tokenIn and the pool's current state.
+/// The bridge fee charged to the sender, if any, is deducted from the amount out. Quotes without
+/// sender are charged the bridge fee.
 pub(crate) fn calc_out_amt_given_in(
     deps: Deps,
     token_in: Coin,
     token_out_denom: String,
+    swap_fee: Decimal,
+    sender: Option<&Addr>,
 ) -> StdResult<CalcOutAmtGivenInResponse> {
-    let config = CONFIG.load(deps.storage)?;
-
-    assert_denoms(deps, token_in.clone().denom, token_out_denom.clone())?;
+    let route = get_swap_route(deps, &token_in.denom, &token_out_denom)?;
+    if route.is_none() {
+        assert_denoms(deps, token_in.clone().denom, token_out_denom.clone())?;
+    }
+    assert_swap_fee(deps, swap_fee)?;
 
-    let swap_simulation: SimulationResponse =
-        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: config.white_whale_pool.to_string(),
-            msg: to_json_binary(&white_whale_std::pool_network::pair::QueryMsg::Simulation {
-                offer_asset: Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: token_in.denom,
-                    },
-                    amount: token_in.amount,
-                },
-            })?,
-        }))?;
+    let amount = match route {
+        Some(operations) => simulate_swap_operations(deps, token_in.amount, operations)?,
+        None => simulate_swap(deps, token_in, &token_out_denom)?.return_amount,
+    };
+    let bridge_fee = amount * get_bridge_fee_share(deps, sender)?;
 
     Ok(CalcOutAmtGivenInResponse {
         token_out: Coin {
             denom: token_out_denom,
-            amount: swap_simulation.return_amount,
+            amount: amount.checked_sub(bridge_fee)?,
         },
     })
 }
 
 /// CalcInAmtGivenOut calculates the amount of tokenIn given tokenOut and the pool's current state.
+/// The bridge fee charged to the sender, if any, is added on top of the amount out swapped. Quotes
+/// without sender are charged the bridge fee.
 pub(crate) fn calc_in_amt_given_out(
     deps: Deps,
     token_out: Coin,
     token_in_denom: String,
+    swap_fee: Decimal,
+    sender: Option<&Addr>,
 ) -> StdResult<CalcInAmtGivenOutResponse> {
-    let config = CONFIG.load(deps.storage)?;
-
+    if get_swap_route(deps, &token_in_denom, &token_out.denom)?.is_some() {
+        return Err(StdError::generic_err(
+            "Routed swaps only support swapping an exact amount in",
+        ));
+    }
     assert_denoms(deps, token_out.clone().denom, token_in_denom.clone())?;
+    assert_swap_fee(deps, swap_fee)?;
+
+    // the pool must return enough tokens to cover the bridge fee on top of the token out amount
+    let token_out = coin(
+        gross_amount_out(token_out.amount, get_bridge_fee_share(deps, sender)?)?.u128(),
+        token_out.denom,
+    );
+
+    let mut offer_amount =
+        reverse_simulate_swap(deps, token_out.clone(), &token_in_denom)?.offer_amount;
 
-    let reverse_swap_simulation: ReverseSimulationResponse =
+    // the reverse simulation rounds down, so the offer amount it returns may fall short of the
+    // token out amount by some dust when swapped. Top it up until it covers the token out amount.
+    for _ in 0..MAX_OFFER_AMOUNT_ADJUSTMENTS {
+        let return_amount = simulate_swap(
+            deps,
+            coin(offer_amount.u128(), token_in_denom.clone()),
+            &token_out.denom,
+        )?
+        .return_amount;
+
+        if return_amount >= token_out.amount {
+            return Ok(CalcInAmtGivenOutResponse {
+                token_in: Coin {
+                    denom: token_in_denom,
+                    amount: offer_amount,
+                },
+            });
+        }
+
+        let shortfall = token_out.amount.checked_sub(return_amount)?;
+        let shortfall_offer_amount = reverse_simulate_swap(
+            deps,
+            coin(shortfall.u128(), token_out.denom.clone()),
+            &token_in_denom,
+        )?
+        .offer_amount;
+
+        offer_amount = offer_amount.checked_add(shortfall_offer_amount.max(Uint128::one()))?;
+    }
+
+    Err(StdError::generic_err(format!(
+        "Couldn't compute the amount of {} needed to get {}",
+        token_in_denom, token_out
+    )))
+}
+
+/// Gets the swap operations the swaps going from `offer_denom` to `ask_denom` are routed through,
+/// if any
+pub(crate) fn get_swap_route(
+    deps: Deps,
+    offer_denom: &str,
+    ask_denom: &str,
+) -> StdResult<Option<Vec<SwapOperation>>> {
+    SWAP_ROUTES.may_load(deps.storage, (offer_denom, ask_denom))
+}
+
+/// Simulates executing the given swap operations on the White Whale router
+fn simulate_swap_operations(
+    deps: Deps,
+    offer_amount: Uint128,
+    operations: Vec<SwapOperation>,
+) -> StdResult<Uint128> {
+    let router = CONFIG
+        .load(deps.storage)?
+        .router
+        .ok_or_else(|| StdError::generic_err("The router is not set"))?;
+
+    let simulation: SimulateSwapOperationsResponse =
         deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: config.white_whale_pool.to_string(),
+            contract_addr: router.to_string(),
             msg: to_json_binary(
-                &white_whale_std::pool_network::pair::QueryMsg::ReverseSimulation {
-                    ask_asset: Asset {
-                        info: AssetInfo::NativeToken {
-                            denom: token_out.denom,
-                        },
-                        amount: token_out.amount,
-                    },
+                &white_whale_std::pool_network::router::QueryMsg::SimulateSwapOperations {
+                    offer_amount,
+                    operations,
                 },
             )?,
         }))?;
 
-    Ok(CalcInAmtGivenOutResponse {
-        token_in: Coin {
-            denom: token_in_denom,
-            amount: reverse_swap_simulation.offer_amount,
-        },
-    })
+    Ok(simulation.amount)
+}
+
+/// Simulates a reverse swap on the White Whale pool, i.e. how much of the offer asset is needed
+/// to get the ask asset
+fn reverse_simulate_swap(
+    deps: Deps,
+    ask: Coin,
+    offer_denom: &str,
+) -> StdResult<ReverseSimulationResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let ask_asset = Asset {
+        info: denom_to_asset_info(deps, &ask.denom)?,
+        amount: ask.amount,
+    };
+
+    let msg = match config.pool_type {
+        PoolType::Pair => to_json_binary(
+            &white_whale_std::pool_network::pair::QueryMsg::ReverseSimulation { ask_asset },
+        )?,
+        PoolType::Trio => to_json_binary(
+            &white_whale_std::pool_network::trio::QueryMsg::ReverseSimulation {
+                ask_asset,
+                offer_asset: Asset {
+                    info: denom_to_asset_info(deps, offer_denom)?,
+                    amount: Uint128::zero(),
+                },
+            },
+        )?,
+    };
+
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: config.white_whale_pool.to_string(),
+        msg,
+    }))
 }
 
 /// Assets the denoms are in the pool
-fn assert_denoms(deps: Deps, token_0: String, token_1: String) -> StdResult<()> {
+pub(crate) fn assert_denoms(deps: Deps, token_0: String, token_1: String) -> StdResult<()> {
     let pool = get_pool(deps)?;
 
-    let asset_0 = pool.assets.iter().any(|asset| match asset.clone().info {
-        AssetInfo::Token { .. } => false,
-        AssetInfo::NativeToken { denom } => denom == token_0,
-    });
-    let asset_1 = pool.assets.iter().any(|asset| match asset.clone().info {
-        AssetInfo::Token { .. } => false,
-        AssetInfo::NativeToken { denom } => denom == token_1,
-    });
-
-    if asset_0 && asset_1 {
-        Ok(())
-    } else if !asset_0 {
-        Err(StdError::generic_err(format!(
-            "Asset {} not found in the pool",
-            token_0
-        )))
-    } else {
-        Err(StdError::generic_err(format!(
-            "Asset {} not found in the pool",
-            token_1
-        )))
+    for token in [token_0, token_1] {
+        if find_asset_amount_by_denom(deps, &pool.assets, &token)?.is_none() {
+            return Err(StdError::generic_err(format!(
+                "Asset {} not found in the pool",
+                token
+            )));
+        }
     }
+
+    Ok(())
+}
+
+/// Queries the routes swaps are executed through by the White Whale router
+pub(crate) fn get_swap_routes(deps: Deps) -> StdResult<SwapRoutesResponse> {
+    let swap_routes = SWAP_ROUTES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let ((offer_denom, ask_denom), operations) = item?;
+            Ok(SwapRoute {
+                offer_denom,
+                ask_denom,
+                operations,
+            })
+        })
+        .collect::<StdResult<Vec<SwapRoute>>>()?;
+
+    Ok(SwapRoutesResponse { swap_routes })
+}
+
+/// Queries the CW20 tokens of the pool and the tokenfactory denoms wrapping them
+pub(crate) fn get_cw20_denoms(deps: Deps) -> StdResult<Cw20DenomsResponse> {
+    let cw20_denoms = CW20_WRAPPED_DENOMS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (contract_addr, denom) = item?;
+            Ok(Cw20Denom {
+                contract_addr,
+                denom,
+            })
+        })
+        .collect::<StdResult<Vec<Cw20Denom>>>()?;
+
+    Ok(Cw20DenomsResponse { cw20_denoms })
+}
+
+/// Queries what migrating the contract to the target version would do, running the same checks
+/// as the migration
+pub(crate) fn get_migration_plan(
+    deps: Deps,
+    target_version: String,
+) -> StdResult<MigrationPlanResponse> {
+    let target_version: Version = target_version
+        .parse()
+        .map_err(|err: semver::Error| StdError::generic_err(err.to_string()))?;
+    let (stored_version, steps) = migrations::plan_migration(deps.storage, &target_version)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    // the version is always stored after migrating
+    let mut affected_keys = vec![CONTRACT_INFO_KEY.to_string()];
+    for key in steps.iter().flat_map(|step| step.affected_keys.iter()) {
+        if !affected_keys.iter().any(|affected_key| affected_key == key) {
+            affected_keys.push(key.to_string());
+        }
+    }
+
+    Ok(MigrationPlanResponse {
+        stored_version: stored_version.to_string(),
+        target_version: target_version.to_string(),
+        steps: steps
+            .into_iter()
+            .map(|step| MigrationStepInfo {
+                version: step.version.to_string(),
+                description: step.description.to_string(),
+                affected_keys: step
+                    .affected_keys
+                    .iter()
+                    .map(|key| key.to_string())
+                    .collect(),
+            })
+            .collect(),
+        affected_keys,
+    })
 }
@@ -0,0 +1,142 @@
+use cosmwasm_std::{Decimal256, StdError, StdResult, Uint128, Uint256};
+
+/// Maximum number of iterations used to approximate the invariant with Newton's method
+const MAX_ITERATIONS: u8 = 64;
+
+/// Computes the StableSwap invariant D for the given pool reserves, normalized to the same
+/// precision, the same way the White Whale stableswap pools do.
+fn compute_d(amp: u64, reserves: &[Uint256]) -> StdResult<Uint256> {
+    let n_coins = Uint256::from(reserves.len() as u128);
+    let sum_x = reserves
+        .iter()
+        .try_fold(Uint256::zero(), |acc, x| acc.checked_add(*x))?;
+
+    if sum_x.is_zero() {
+        return Ok(Uint256::zero());
+    }
+
+    let leverage = Uint256::from(amp).checked_mul(n_coins)?;
+    let mut d = sum_x;
+
+    for _ in 0..MAX_ITERATIONS {
+        let d_product = compute_d_product(d, reserves)?;
+        let d_prev = d;
+
+        // d = (leverage * sum_x + d_product * n_coins) * d / ((leverage - 1) * d + (n_coins + 1) * d_product)
+        let numerator = leverage
+            .checked_mul(sum_x)?
+            .checked_add(d_product.checked_mul(n_coins)?)?
+            .checked_mul(d)?;
+        let denominator = leverage
+            .checked_sub(Uint256::one())?
+            .checked_mul(d)?
+            .checked_add(
+                n_coins
+                    .checked_add(Uint256::one())?
+                    .checked_mul(d_product)?,
+            )?;
+        d = numerator.checked_div(denominator)?;
+
+        if d.max(d_prev) - d.min(d_prev) <= Uint256::one() {
+            return Ok(d);
+        }
+    }
+
+    Err(StdError::generic_err(
+        "The StableSwap invariant didn't converge",
+    ))
+}
+
+/// Computes D^(n+1) / (n^n * prod(x_i)), the product term of the StableSwap invariant
+fn compute_d_product(d: Uint256, reserves: &[Uint256]) -> StdResult<Uint256> {
+    let n_coins = Uint256::from(reserves.len() as u128);
+
+    reserves.iter().try_fold(d, |d_product, x| {
+        Ok(d_product
+            .checked_mul(d)?
+            .checked_div(x.checked_mul(n_coins)?)?)
+    })
+}
+
+/// Computes the marginal price of the base asset in terms of the quote asset out of the
+/// StableSwap invariant, i.e. the amount of quote asset received per unit of base asset for an
+/// infinitesimal swap, without fees, along with the inverse price. Both come out of a single
+/// invariant computation. The reserves are normalized to the highest precision among the assets
+/// before computing the invariant, and the prices are scaled back to their own precision.
+pub(crate) fn spot_prices(
+    amp: u64,
+    reserves: &[Uint128],
+    decimals: &[u8],
+    base_index: usize,
+    quote_index: usize,
+) -> StdResult<(Decimal256, Decimal256)> {
+    let max_decimals = decimals.iter().copied().max().unwrap_or_default();
+    let normalized_reserves = reserves
+        .iter()
+        .zip(decimals)
+        .map(|(reserve, decimals)| {
+            Ok(Uint256::from(*reserve).checked_mul(
+                Uint256::from(10u128).checked_pow((max_decimals - decimals) as u32)?,
+            )?)
+        })
+        .collect::<StdResult<Vec<Uint256>>>()?;
+
+    let (price, inverse_price) =
+        marginal_prices(amp, &normalized_reserves, base_index, quote_index)?;
+
+    // a unit of base asset is 10^(max_decimals - base_decimals) normalized units, while a
+    // normalized unit of quote asset is 10^(quote_decimals - max_decimals) units
+    let (base_decimals, quote_decimals) = (decimals[base_index], decimals[quote_index]);
+    let scale = Decimal256::from_ratio(
+        Uint256::from(10u128).checked_pow(base_decimals.abs_diff(quote_decimals) as u32)?,
+        1u8,
+    );
+    let div_scale = |price: Decimal256| {
+        price
+            .checked_div(scale)
+            .map_err(|err| StdError::generic_err(err.to_string()))
+    };
+
+    if quote_decimals >= base_decimals {
+        Ok((price.checked_mul(scale)?, div_scale(inverse_price)?))
+    } else {
+        Ok((div_scale(price)?, inverse_price.checked_mul(scale)?))
+    }
+}
+
+/// Computes the marginal price of the asset at `base_index` in terms of the asset at
+/// `quote_index`, and the inverse one, given the normalized reserves.
+fn marginal_prices(
+    amp: u64,
+    reserves: &[Uint256],
+    base_index: usize,
+    quote_index: usize,
+) -> StdResult<(Decimal256, Decimal256)> {
+    let n_coins = Uint256::from(reserves.len() as u128);
+    let d = compute_d(amp, reserves)?;
+    let d_product = compute_d_product(d, reserves)?;
+    let leverage = Decimal256::from_ratio(Uint256::from(amp).checked_mul(n_coins)?, 1u8);
+
+    // the partial derivative of the invariant with respect to the reserve x_k is
+    // leverage + d_product / x_k, the marginal price is the ratio of the partial derivatives
+    let partial_derivative = |index: usize| -> StdResult<Decimal256> {
+        let d_product_over_x = Decimal256::checked_from_ratio(d_product, reserves[index])
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+        Ok(leverage.checked_add(d_product_over_x)?)
+    };
+    let (base_derivative, quote_derivative) = (
+        partial_derivative(base_index)?,
+        partial_derivative(quote_index)?,
+    );
+
+    let ratio = |numerator: Decimal256, denominator: Decimal256| {
+        numerator
+            .checked_div(denominator)
+            .map_err(|err| StdError::generic_err(err.to_string()))
+    };
+
+    Ok((
+        ratio(base_derivative, quote_derivative)?,
+        ratio(quote_derivative, base_derivative)?,
+    ))
+}
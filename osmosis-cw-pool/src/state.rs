@@ -1,11 +1,116 @@
-use cw_storage_plus::Item;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Empty, Uint128};
+use cw_storage_plus::{Item, Map};
+use white_whale_std::pool_network::router::SwapOperation;
 
-use crate::msg::{Config, MinimumReceiveAssertion};
+use crate::msg::{
+    ActiveWindow, BridgeFee, CircuitBreakerConfig, Config, DenomPause, ExactAmountOutAssertion,
+    ExitPoolData, MinimumReceiveAssertion, PreSwapPrice, PriceObservation, RateLimit,
+    RateLimitUsage, SenderAccessMode, SingleSidedJoinData, SlippageConfig, SpotPriceConfig,
+    SwapVolume, TwapRecord,
+};
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// the owner proposed by the current owner, pending to accept the ownership
+pub const PENDING_OWNER: Item<Addr> = Item::new("pending_owner");
+
+/// The config stored by 1.0.x, which only had the White Whale pool
+#[cw_serde]
+pub struct ConfigV102 {
+    pub white_whale_pool: Addr,
+}
+
+pub const CONFIG_V102: Item<ConfigV102> = Item::new("config");
+
 pub const IS_ACTIVE: Item<bool> = Item::new("is_active");
 
-/// temp variables for storing assertion data when doing swaps
-pub const TEMP_MIN_ASSERTION_DATA: Item<MinimumReceiveAssertion> =
+/// the window the active status applies in, having the opposite one outside of it
+pub const ACTIVE_WINDOW: Item<ActiveWindow> = Item::new("active_window");
+
+/// swap directions paused per denom, on top of the pool active status
+pub const DENOM_PAUSES: Map<&str, DenomPause> = Map::new("denom_pauses");
+
+pub const SLIPPAGE_CONFIG: Item<SlippageConfig> = Item::new("slippage_config");
+
+pub const SPOT_PRICE_CONFIG: Item<SpotPriceConfig> = Item::new("spot_price_config");
+
+/// max spread overrides, keyed by (offer_denom, ask_denom)
+pub const MAX_SPREAD_OVERRIDES: Map<(&str, &str), Decimal> = Map::new("max_spread_overrides");
+
+/// the fee charged on the swaps going through the bridge, if any
+pub const BRIDGE_FEE: Item<BridgeFee> = Item::new("bridge_fee");
+
+/// senders exempted from the bridge fee
+pub const BRIDGE_FEE_EXEMPTIONS: Map<&Addr, Empty> = Map::new("bridge_fee_exemptions");
+
+/// how the sender access list is applied to swaps, if at all
+pub const SENDER_ACCESS_MODE: Item<SenderAccessMode> = Item::new("sender_access_mode");
+
+/// senders allowed or denied to swap, depending on the sender access mode
+pub const SENDER_ACCESS_LIST: Map<&Addr, Empty> = Map::new("sender_access_list");
+
+/// bridge fees collected so far, keyed by denom
+pub const ACCRUED_BRIDGE_FEES: Map<&str, Uint128> = Map::new("accrued_bridge_fees");
+
+/// the price bands swaps can't move the spot price beyond, if any
+pub const CIRCUIT_BREAKER_CONFIG: Item<CircuitBreakerConfig> = Item::new("circuit_breaker_config");
+
+/// spot prices observed around the swaps, keyed by (base_denom, quote_denom) sorted alphabetically
+pub const PRICE_OBSERVATIONS: Map<(&str, &str), PriceObservation> = Map::new("price_observations");
+
+/// caps on the volume swapped out of the pool per window, keyed by denom
+pub const RATE_LIMITS: Map<&str, RateLimit> = Map::new("rate_limits");
+
+/// volume swapped out of the pool in the current window, keyed by denom
+pub const RATE_LIMIT_USAGES: Map<&str, RateLimitUsage> = Map::new("rate_limit_usages");
+
+/// volume swapped out of the pool by each sender in its last window, keyed by (denom, sender)
+pub const SENDER_RATE_LIMIT_USAGES: Map<(&str, &str), RateLimitUsage> =
+    Map::new("sender_rate_limit_usages");
+
+/// swap operations executed by the router, keyed by (offer_denom, ask_denom)
+pub const SWAP_ROUTES: Map<(&str, &str), Vec<SwapOperation>> = Map::new("swap_routes");
+
+/// denoms wrapping the CW20 tokens in the pool, keyed by CW20 contract address
+pub const CW20_WRAPPED_DENOMS: Map<&str, String> = Map::new("cw20_wrapped_denoms");
+
+/// CW20 tokens wrapped by the contract, keyed by wrapped denom
+pub const WRAPPED_DENOM_CW20S: Map<&str, String> = Map::new("wrapped_denom_cw20s");
+
+/// temp variables for storing assertion data when doing swaps, keyed by the reply id of the swap
+/// so swaps landing in the same transaction don't overwrite each other's data
+pub const TEMP_MIN_ASSERTION_DATA: Map<u64, MinimumReceiveAssertion> =
+    Map::new("temp_min_assertions");
+pub const TEMP_EXACT_OUT_ASSERTION_DATA: Map<u64, ExactAmountOutAssertion> =
+    Map::new("temp_exact_out_assertions");
+
+/// spot prices recorded by the swaps to compute TWAPs, keyed by (base_denom, quote_denom, time)
+/// with the time in seconds
+pub const TWAP_RECORDS: Map<(&str, &str, u64), TwapRecord> = Map::new("twap_records");
+
+/// number of swaps executed through the pool
+pub const SWAP_COUNT: Item<u64> = Item::new("swap_count");
+
+/// volumes swapped since the pool was created, keyed by denom
+pub const SWAP_VOLUMES: Map<&str, SwapVolume> = Map::new("swap_volumes");
+
+/// volumes swapped each day, keyed by (denom, days since the unix epoch)
+pub const DAILY_SWAP_VOLUMES: Map<(&str, u64), SwapVolume> = Map::new("daily_swap_volumes");
+
+/// temp variable for storing the spot price before a swap, keyed by the reply id of the swap
+pub const TEMP_PRE_SWAP_PRICES: Map<u64, PreSwapPrice> = Map::new("temp_pre_swap_prices");
+
+/// nonce used to give a unique reply id to every swap, join and exit
+pub const REPLY_ID_NONCE: Item<u64> = Item::new("swap_reply_id_nonce");
+
+/// the assertion data stored by 1.0.x, shared by all the swaps
+pub const TEMP_MIN_ASSERTION_DATA_V102: Item<MinimumReceiveAssertion> =
     Item::new("temp_min_assertion_data");
+
+/// temp variables for storing the liquidity data when joining or exiting the pool, keyed by the
+/// reply id of the join or exit, so the ones landing in the same transaction don't overwrite each
+/// other's data
+pub const TEMP_SINGLE_SIDED_JOIN_DATA: Map<u64, SingleSidedJoinData> =
+    Map::new("temp_single_sided_joins");
+pub const TEMP_EXIT_POOL_DATA: Map<u64, ExitPoolData> = Map::new("temp_exit_pools");
@@ -1,15 +1,31 @@
-use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use cosmwasm_std::{coin, Addr, Decimal, Decimal256, Event, Uint128};
+use cw_utils::Duration;
+use osmosis_std::types::osmosis::incentives::MsgCreateGauge;
+use osmosis_std::types::osmosis::lockup::{LockQueryType, QueryCondition};
 use osmosis_std::types::osmosis::poolmanager::v1beta1::{
     MsgSwapExactAmountInResponse, MsgSwapExactAmountOutResponse,
 };
 use osmosis_test_tube::{Account, RunnerError};
 use white_whale_std::fee::Fee;
-use white_whale_std::pool_network::asset::{Asset, AssetInfo};
+use white_whale_std::pool_network::asset::{Asset, AssetInfo, PairType};
 use white_whale_std::pool_network::pair::{PoolFee, PoolResponse};
+use white_whale_std::pool_network::router::SwapOperation;
 
 use osmosis_cw_pool::msg::{
-    CalcInAmtGivenOutResponse, CalcOutAmtGivenInResponse, Config, GetSwapFeeResponse,
-    IsActiveResponse, QueryMsg, SpotPriceResponse, TotalPoolLiquidityResponse,
+    ActiveStatusResponse, ActiveWindow, BridgeFee, BridgeFeeResponse, CalcInAmtGivenOutResponse,
+    CalcOutAmtGivenInResponse, CircuitBreakerConfig, CircuitBreakerResponse, Config,
+    CreatePoolGauges, Cw20DenomsResponse, DenomPause, DenomSwapVolume, ExecuteMsg,
+    GetSwapFeeResponse, IsActiveResponse, MaxSpreadOverride, OwnershipResponse, PausedDenom,
+    PoolType, PreciseSpotPriceResponse, QueryMsg, RateLimit, RateLimitResponse, RateLimitsResponse,
+    SenderAccessListResponse, SenderAccessMode, ShareDenomResponse, SimulateExitPoolResponse,
+    SimulateJoinPoolResponse, SlippageConfigResponse, SpotPriceConfig, SpotPriceResponse,
+    StatsResponse, SwapDirection, SwapDirectionsResponse, SwapRoute, SwapRoutesResponse,
+    SwapVolume, TotalPoolLiquidityResponse, TotalSharesResponse, TwapResponse,
+    VolumeHistoryResponse,
 };
 
 use crate::suite::TestingSuite;
@@ -167,7 +183,7 @@ fn swap_tokens_in() {
         .query_osmosis_pool_interface(QueryMsg::CalcOutAmtGivenIn {
             token_in: coin(10_000, "uosmo"),
             token_out_denom: "uwhale".to_string(),
-            swap_fee: Default::default(),
+            swap_fee: Decimal::permille(3),
         }, |result: Result<CalcOutAmtGivenInResponse, RunnerError>| {
             let response = result.unwrap();
             assert_eq!(
@@ -194,6 +210,13 @@ fn swap_tokens_in() {
         )
         .check_address_balance(new_account.address().clone(), "uwhale".into(), |amount| {
             assert_eq!(amount, Uint128::new(9_963));
+        });
+
+    // the contract receives the swapped tokens and forwards all of them to the sender
+    let cw_osmosis_pool_interface = suite.cw_osmosis_pool_interface.clone();
+    suite
+        .check_address_balance(cw_osmosis_pool_interface, "uwhale".into(), |amount| {
+            assert_eq!(amount, Uint128::zero());
         })
         .swap_token_in(
             &new_account,
@@ -343,13 +366,13 @@ fn swap_tokens_out() {
         .query_osmosis_pool_interface(QueryMsg::CalcInAmtGivenOut {
             token_out: coin(10_000, "uwhale"),
             token_in_denom: "uosmo".to_string(),
-            swap_fee: Default::default(),
+            swap_fee: Decimal::permille(3),
         }, |result: Result<CalcInAmtGivenOutResponse, RunnerError>| {
             let response = result.unwrap();
             assert_eq!(
                 response,
                 CalcInAmtGivenOutResponse {
-                    token_in: coin(10_040, "uosmo")
+                    token_in: coin(10_041, "uosmo")
                 }
             );
         })
@@ -364,7 +387,7 @@ fn swap_tokens_out() {
                     err,
                     RunnerError::ExecuteError {
                         msg: "failed to execute message; message index: 0: SwapExactAmountOut used more tokens than \
-                        allowed. Maximum token in amount: 10000, token in used: 10040: execute wasm contract failed".to_string()
+                        allowed. Maximum token in amount: 10000, token in used: 10041: execute wasm contract failed".to_string()
                     }
                 );
             },
@@ -382,13 +405,96 @@ fn swap_tokens_out() {
                 assert_eq!(
                     response.data,
                     MsgSwapExactAmountOutResponse {
-                        token_in_amount: "10040".to_string()
+                        token_in_amount: "10041".to_string()
                     }
                 );
             },
         )
         .check_address_balance(new_account.address().clone(), "uwhale".into(), |amount| {
-            assert_eq!(amount, Uint128::new(9_999));
+            assert_eq!(amount, Uint128::new(10_000));
+        });
+}
+
+#[test]
+fn swap_tokens_out_rounding_surplus() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    // a single uwhale buys about a million uosmo
+    suite.provide_liquidity([
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            amount: Uint128::new(1_000_000_000),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+            amount: Uint128::new(1_000),
+        },
+    ]);
+
+    let new_account = suite
+        .app
+        .init_account(&[
+            coin(10_000_000_000, "uosmo"),
+            coin(10_000_000_000, "uwhale"),
+        ])
+        .unwrap();
+
+    // one uwhale isn't enough to get the token out amount, so two are swapped and the surplus they
+    // buy is forwarded along with it
+    suite
+        .create_cosmwasm_pool()
+        .swap_token_out(
+            &new_account,
+            coin(1_500_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(10),
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(response.data.token_in_amount, "2");
+
+                let settlement = wasm_event_attributes(&response.events, "swap_settlement");
+                let sent_amount: u128 = settlement["sent_amount"].parse().unwrap();
+                assert_eq!(settlement["token_out_min_amount"], "1500000");
+                assert_eq!(settlement["token_out_amount"], settlement["sent_amount"]);
+                assert!(sent_amount > 1_500_000);
+            },
+        )
+        // only the token in used is taken, the rest of the maximum amount is refunded
+        .check_address_balance(new_account.address().clone(), "uwhale".into(), |amount| {
+            assert_eq!(amount, Uint128::new(10_000_000_000 - 2));
         });
 }
 
@@ -426,6 +532,7 @@ fn check_queries() {
     );
 
     let ww_pool = Addr::unchecked(suite.ww_pool_addr.clone());
+    let owner = Addr::unchecked(suite.accounts[&0].address());
 
     suite
         .provide_liquidity([
@@ -456,7 +563,7 @@ fn check_queries() {
             QueryMsg::CalcOutAmtGivenIn {
                 token_in: coin(10_000, "uosmo"),
                 token_out_denom: "uwhale".to_string(),
-                swap_fee: Default::default(),
+                swap_fee: Decimal::permille(3),
             },
             |result: Result<CalcOutAmtGivenInResponse, RunnerError>| {
                 let response = result.unwrap();
@@ -472,18 +579,45 @@ fn check_queries() {
             QueryMsg::CalcInAmtGivenOut {
                 token_out: coin(10_000, "uwhale"),
                 token_in_denom: "uosmo".to_string(),
-                swap_fee: Default::default(),
+                swap_fee: Decimal::permille(3),
             },
             |result: Result<CalcInAmtGivenOutResponse, RunnerError>| {
                 let response = result.unwrap();
                 assert_eq!(
                     response,
                     CalcInAmtGivenOutResponse {
-                        token_in: coin(10_040, "uosmo")
+                        token_in: coin(10_041, "uosmo")
                     }
                 );
             },
         )
+        .query_osmosis_pool_interface(
+            QueryMsg::CalcOutAmtGivenIn {
+                token_in: coin(10_000, "uosmo"),
+                token_out_denom: "uwhale".to_string(),
+                swap_fee: Decimal::percent(1),
+            },
+            |result: Result<CalcOutAmtGivenInResponse, RunnerError>| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("Swap fee mismatch. Provided swap fee: 0.01, pool swap fee: 0.003"));
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::CalcOutAmtGivenIn {
+                token_in: coin(10_000, "uosmo"),
+                token_out_denom: "uwhale".to_string(),
+                // the pool's swap fee alone, without the protocol and osmosis fees it also charges
+                swap_fee: Decimal::permille(1),
+            },
+            |result: Result<CalcOutAmtGivenInResponse, RunnerError>| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("Swap fee mismatch. Provided swap fee: 0.001, pool swap fee: 0.003"));
+            },
+        )
         .query_osmosis_pool_interface(
             QueryMsg::GetSwapFee {},
             |result: Result<GetSwapFeeResponse, RunnerError>| {
@@ -491,7 +625,7 @@ fn check_queries() {
                 assert_eq!(
                     response,
                     GetSwapFeeResponse {
-                        swap_fee: Decimal::permille(1)
+                        swap_fee: Decimal::permille(3)
                     }
                 );
             },
@@ -598,6 +732,53 @@ fn check_queries() {
                 );
             },
         )
+        .query_osmosis_pool_interface(
+            QueryMsg::PreciseSpotPrice {
+                quote_asset_denom: "uwhale".to_string(),
+                base_asset_denom: "uosmo".to_string(),
+            },
+            |result: Result<PreciseSpotPriceResponse, RunnerError>| {
+                let res = result.unwrap();
+                assert_eq!(
+                    res,
+                    PreciseSpotPriceResponse {
+                        spot_price: Decimal256::from_ratio(
+                            Uint128::from(9_990_019u128),
+                            Uint128::from(10_010_000u128)
+                        )
+                    }
+                );
+            },
+        )
+        .update_spot_price_config(true, |result| {
+            result.unwrap();
+        })
+        .query_osmosis_pool_interface(
+            QueryMsg::GetSpotPriceConfig {},
+            |result: Result<SpotPriceConfig, RunnerError>| {
+                let res = result.unwrap();
+                assert_eq!(res, SpotPriceConfig { include_fees: true });
+            },
+        )
+        // the protocol, swap and osmosis fees add up to 0.3%
+        .query_osmosis_pool_interface(
+            QueryMsg::SpotPrice {
+                quote_asset_denom: "uwhale".to_string(),
+                base_asset_denom: "uosmo".to_string(),
+            },
+            |result: Result<SpotPriceResponse, RunnerError>| {
+                let res = result.unwrap();
+                assert_eq!(
+                    res,
+                    SpotPriceResponse {
+                        spot_price: Decimal::from_ratio(
+                            Uint128::from(9_990_019u128),
+                            Uint128::from(10_010_000u128)
+                        ) * (Decimal::one() - Decimal::permille(3))
+                    }
+                );
+            },
+        )
         .set_active(false, |result| {
             result.unwrap();
         })
@@ -615,9 +796,3185 @@ fn check_queries() {
                 assert_eq!(
                     res,
                     Config {
+                        owner: owner.clone(),
                         white_whale_pool: ww_pool.clone(),
+                        pool_type: PoolType::Pair,
+                        lp_token: AssetInfo::NativeToken {
+                            denom: format!("factory/{}/uLP", ww_pool),
+                        },
+                        router: None,
+                    }
+                );
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetTotalShares {},
+            |result: Result<TotalSharesResponse, RunnerError>| {
+                let res = result.unwrap();
+                assert_eq!(
+                    res,
+                    TotalSharesResponse {
+                        total_shares: Uint128::new(10_000_000),
+                    }
+                );
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetShareDenom {},
+            |result: Result<ShareDenomResponse, RunnerError>| {
+                let res = result.unwrap();
+                assert_eq!(
+                    res,
+                    ShareDenomResponse {
+                        share_denom: format!("factory/{}/uLP", ww_pool),
+                    }
+                );
+            },
+        )
+        .register_cw20(new_account.address(), "cw20".to_string(), |result| {
+            let err = result.unwrap_err();
+            assert!(err.to_string().contains("not found in the pool"));
+        })
+        .query_osmosis_pool_interface(
+            QueryMsg::GetCw20Denoms {},
+            |result: Result<Cw20DenomsResponse, RunnerError>| {
+                let res = result.unwrap();
+                assert!(res.cw20_denoms.is_empty());
+            },
+        );
+}
+
+#[test]
+fn swap_fee_includes_all_the_pool_fees() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(2),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(3),
+            },
+            burn_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::from_ratio(5u128, 10_000u128),
+            },
+        },
+    );
+
+    let new_account = suite
+        .app
+        .init_account(&[coin(10_000_000_000, "uosmo")])
+        .unwrap();
+
+    suite
+        .provide_liquidity([
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uosmo".to_string(),
+                },
+                amount: Uint128::new(10_000_000),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uwhale".to_string(),
+                },
+                amount: Uint128::new(10_000_000),
+            },
+        ])
+        .create_cosmwasm_pool()
+        .query_osmosis_pool_interface(
+            QueryMsg::GetSwapFee {},
+            |result: Result<GetSwapFeeResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap().swap_fee,
+                    Decimal::from_ratio(65u128, 10_000u128)
+                );
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::CalcOutAmtGivenIn {
+                token_in: coin(10_000, "uosmo"),
+                token_out_denom: "uwhale".to_string(),
+                swap_fee: Decimal::permille(3),
+            },
+            |result: Result<CalcOutAmtGivenInResponse, RunnerError>| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains(
+                    "Swap fee mismatch. Provided swap fee: 0.003, pool swap fee: 0.0065"
+                ));
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::CalcOutAmtGivenIn {
+                token_in: coin(10_000, "uosmo"),
+                token_out_denom: "uwhale".to_string(),
+                swap_fee: Decimal::from_ratio(65u128, 10_000u128),
+            },
+            |result: Result<CalcOutAmtGivenInResponse, RunnerError>| {
+                // 9_990 uwhale out of the pool, minus 19 + 29 + 9 + 4 uwhale of fees
+                assert_eq!(result.unwrap().token_out, coin(9_929, "uwhale"));
+            },
+        )
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_929),
+            |result| {
+                assert_eq!(
+                    result.unwrap().data,
+                    MsgSwapExactAmountInResponse {
+                        token_out_amount: "9929".to_string()
                     }
                 );
             },
         );
 }
+
+#[test]
+fn precise_spot_price_range() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    // a uosmo is worth 10^21 uwhale, beyond the range of a Decimal
+    suite
+        .provide_liquidity([
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uosmo".to_string(),
+                },
+                amount: Uint128::new(1_000_000),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uwhale".to_string(),
+                },
+                amount: Uint128::new(1_000_000_000_000_000_000_000_000_000),
+            },
+        ])
+        .create_cosmwasm_pool()
+        .query_osmosis_pool_interface(
+            QueryMsg::SpotPrice {
+                quote_asset_denom: "uwhale".to_string(),
+                base_asset_denom: "uosmo".to_string(),
+            },
+            |result: Result<SpotPriceResponse, RunnerError>| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Decimal range exceeded"));
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::PreciseSpotPrice {
+                quote_asset_denom: "uwhale".to_string(),
+                base_asset_denom: "uosmo".to_string(),
+            },
+            |result: Result<PreciseSpotPriceResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap().spot_price,
+                    Decimal256::from_ratio(10u128.pow(21), 1u128)
+                );
+            },
+        );
+}
+
+#[test]
+fn slippage_config() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    suite
+        .provide_liquidity([
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uosmo".to_string(),
+                },
+                amount: Uint128::new(10_000_000),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uwhale".to_string(),
+                },
+                amount: Uint128::new(10_000_000),
+            },
+        ])
+        .create_cosmwasm_pool()
+        .query_osmosis_pool_interface(
+            QueryMsg::GetSlippageConfig {},
+            |result: Result<SlippageConfigResponse, RunnerError>| {
+                let res = result.unwrap();
+                assert_eq!(
+                    res,
+                    SlippageConfigResponse {
+                        max_spread: Decimal::percent(30),
+                        use_belief_price: false,
+                        max_spread_overrides: vec![],
+                    }
+                );
+            },
+        )
+        .update_slippage_config(Some(Decimal::percent(60)), None, |result| {
+            let err = result.unwrap_err();
+            assert!(err
+                .to_string()
+                .contains("Invalid max spread 0.6, it can't be higher than 0.5"));
+        })
+        .set_max_spread_override(
+            "uosmo".to_string(),
+            "usdc".to_string(),
+            Some(Decimal::percent(1)),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Asset usdc not found in the pool"));
+            },
+        )
+        .set_max_spread_override(
+            "uosmo".to_string(),
+            "uwhale".to_string(),
+            Some(Decimal::percent(1)),
+            |result| {
+                result.unwrap();
+            },
+        )
+        .update_slippage_config(None, Some(true), |result| {
+            result.unwrap();
+        })
+        .query_osmosis_pool_interface(
+            QueryMsg::GetSlippageConfig {},
+            |result: Result<SlippageConfigResponse, RunnerError>| {
+                let res = result.unwrap();
+                assert_eq!(
+                    res,
+                    SlippageConfigResponse {
+                        max_spread: Decimal::percent(30),
+                        use_belief_price: true,
+                        max_spread_overrides: vec![MaxSpreadOverride {
+                            offer_denom: "uosmo".to_string(),
+                            ask_denom: "uwhale".to_string(),
+                            max_spread: Decimal::percent(1),
+                        }],
+                    }
+                );
+            },
+        );
+
+    let new_account = suite
+        .app
+        .init_account(&[
+            coin(10_000_000_000, "uosmo"),
+            coin(10_000_000_000, "uwhale"),
+        ])
+        .unwrap();
+
+    // a large swap in the overridden direction exceeds the 1% max spread
+    suite
+        .swap_token_in(
+            &new_account,
+            coin(1_000_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(1),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Spread limit exceeded"));
+            },
+        )
+        // the other direction falls back to the default 30% max spread
+        .swap_token_in(
+            &new_account,
+            coin(1_000_000, "uwhale"),
+            "uosmo".to_string(),
+            Uint128::new(1),
+            |result| {
+                result.unwrap();
+            },
+        )
+        .set_max_spread_override("uosmo".to_string(), "uwhale".to_string(), None, |result| {
+            result.unwrap();
+        })
+        .swap_token_in(
+            &new_account,
+            coin(1_000_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(1),
+            |result| {
+                result.unwrap();
+            },
+        );
+}
+
+#[test]
+fn stableswap_spot_price() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool_with_type(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+        PairType::StableSwap { amp: 100 },
+    );
+
+    let ww_pool = Addr::unchecked(suite.ww_pool_addr.clone());
+    let owner = Addr::unchecked(suite.accounts[&0].address());
+
+    suite
+        .provide_liquidity([
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uosmo".to_string(),
+                },
+                amount: Uint128::new(10_000_000),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uwhale".to_string(),
+                },
+                amount: Uint128::new(12_000_000),
+            },
+        ])
+        .create_cosmwasm_pool()
+        .query_osmosis_pool_interface(
+            QueryMsg::GetConfig {},
+            |result: Result<Config, RunnerError>| {
+                assert_eq!(
+                    result.unwrap(),
+                    Config {
+                        owner: owner.clone(),
+                        white_whale_pool: ww_pool.clone(),
+                        pool_type: PoolType::Pair,
+                        lp_token: AssetInfo::NativeToken {
+                            denom: format!("factory/{}/uLP", ww_pool),
+                        },
+                        router: None,
+                    }
+                );
+            },
+        )
+        // the reserve ratio would be 1.2, while the invariant keeps the price close to the peg
+        .query_osmosis_pool_interface(
+            QueryMsg::SpotPrice {
+                quote_asset_denom: "uwhale".to_string(),
+                base_asset_denom: "uosmo".to_string(),
+            },
+            |result: Result<SpotPriceResponse, RunnerError>| {
+                let spot_price = result.unwrap().spot_price;
+                assert!(spot_price > Decimal::one());
+                assert!(spot_price < Decimal::percent(101));
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::SpotPrice {
+                quote_asset_denom: "uosmo".to_string(),
+                base_asset_denom: "uwhale".to_string(),
+            },
+            |result: Result<SpotPriceResponse, RunnerError>| {
+                let spot_price = result.unwrap().spot_price;
+                assert!(spot_price < Decimal::one());
+                assert!(spot_price > Decimal::percent(99));
+            },
+        );
+}
+
+#[test]
+fn ownership() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    // the account 0 is the owner of the contract
+    let (owner, new_owner) = (0, 1);
+    let owner_addr = Addr::unchecked(suite.accounts[&owner].address());
+    let new_owner_addr = Addr::unchecked(suite.accounts[&new_owner].address());
+
+    suite
+        .create_cosmwasm_pool()
+        .execute_osmosis_pool_interface(
+            new_owner,
+            ExecuteMsg::UpdateSlippageConfig {
+                max_spread: Some(Decimal::percent(10)),
+                use_belief_price: None,
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Unauthorized"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            owner,
+            ExecuteMsg::UpdateSlippageConfig {
+                max_spread: Some(Decimal::percent(10)),
+                use_belief_price: None,
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetSlippageConfig {},
+            |result: Result<SlippageConfigResponse, RunnerError>| {
+                assert_eq!(result.unwrap().max_spread, Decimal::percent(10));
+            },
+        )
+        .execute_osmosis_pool_interface(new_owner, ExecuteMsg::AcceptOwnership {}, &[], |result| {
+            let err = result.unwrap_err();
+            assert!(err
+                .to_string()
+                .contains("There's no pending ownership proposal"));
+        })
+        .execute_osmosis_pool_interface(
+            owner,
+            ExecuteMsg::ProposeNewOwner {
+                new_owner: new_owner_addr.to_string(),
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetOwnership {},
+            |result: Result<OwnershipResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap(),
+                    OwnershipResponse {
+                        owner: owner_addr.clone(),
+                        pending_owner: Some(new_owner_addr.clone()),
+                    }
+                );
+            },
+        )
+        .execute_osmosis_pool_interface(owner, ExecuteMsg::AcceptOwnership {}, &[], |result| {
+            let err = result.unwrap_err();
+            assert!(err.to_string().contains("Unauthorized"));
+        })
+        .execute_osmosis_pool_interface(new_owner, ExecuteMsg::AcceptOwnership {}, &[], |result| {
+            result.unwrap();
+        })
+        .query_osmosis_pool_interface(
+            QueryMsg::GetOwnership {},
+            |result: Result<OwnershipResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap(),
+                    OwnershipResponse {
+                        owner: new_owner_addr.clone(),
+                        pending_owner: None,
+                    }
+                );
+            },
+        )
+        .execute_osmosis_pool_interface(
+            owner,
+            ExecuteMsg::SetActive {
+                is_active: false,
+                from: None,
+                until: None,
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Unauthorized"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            new_owner,
+            ExecuteMsg::SetActive {
+                is_active: false,
+                from: None,
+                until: None,
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::IsActive {},
+            |result: Result<IsActiveResponse, RunnerError>| {
+                assert_eq!(result.unwrap(), IsActiveResponse { is_active: false });
+            },
+        );
+}
+
+#[test]
+fn repoint_white_whale_pool() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    let pool_fees = PoolFee {
+        protocol_fee: Fee {
+            share: Decimal::permille(1),
+        },
+        swap_fee: Fee {
+            share: Decimal::permille(1),
+        },
+        burn_fee: Fee {
+            share: Decimal::zero(),
+        },
+        osmosis_fee: Fee {
+            share: Decimal::permille(1),
+        },
+    };
+
+    suite
+        .create_ww_pool(
+            [
+                AssetInfo::NativeToken {
+                    denom: "uosmo".to_string(),
+                },
+                AssetInfo::NativeToken {
+                    denom: "uwhale".to_string(),
+                },
+            ],
+            [6, 6],
+            pool_fees.clone(),
+        )
+        .create_cosmwasm_pool();
+
+    let previous_pool = suite.ww_pool_addr.clone();
+
+    // a pool with different assets can't replace the current one
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uusdc".to_string(),
+            },
+        ],
+        [6, 6],
+        pool_fees.clone(),
+    );
+
+    let mismatching_pool = suite.ww_pool_addr.clone();
+
+    // the assets of the new pool can be in a different order
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+        ],
+        [6, 6],
+        pool_fees,
+    );
+
+    let new_pool = suite.ww_pool_addr.clone();
+
+    suite
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::UpdateConfig {
+                white_whale_pool: Some(new_pool.clone()),
+                router: None,
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Unauthorized"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::UpdateConfig {
+                white_whale_pool: Some(mismatching_pool.clone()),
+                router: None,
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("doesn't hold the same assets as the current one"));
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetConfig {},
+            |result: Result<Config, RunnerError>| {
+                assert_eq!(result.unwrap().white_whale_pool.to_string(), previous_pool);
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::UpdateConfig {
+                white_whale_pool: Some(new_pool.clone()),
+                router: None,
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetConfig {},
+            |result: Result<Config, RunnerError>| {
+                let config = result.unwrap();
+                assert_eq!(config.white_whale_pool.to_string(), new_pool);
+                assert_eq!(config.pool_type, PoolType::Pair);
+            },
+        );
+}
+
+#[test]
+fn join_and_exit_pool() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    let lp_denom = format!("factory/{}/uLP", suite.ww_pool_addr);
+    let lp_provider = suite.accounts[&1].address();
+
+    suite
+        .provide_liquidity([
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uosmo".to_string(),
+                },
+                amount: Uint128::new(10_000_000),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uwhale".to_string(),
+                },
+                amount: Uint128::new(10_000_000),
+            },
+        ])
+        .create_cosmwasm_pool()
+        .query_osmosis_pool_interface(
+            QueryMsg::SimulateJoinPool {
+                tokens_in: vec![coin(1_000_000, "uosmo"), coin(1_000_000, "uwhale")],
+            },
+            |result: Result<SimulateJoinPoolResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap(),
+                    SimulateJoinPoolResponse {
+                        shares_out: Uint128::new(1_000_000),
+                    }
+                );
+            },
+        )
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::JoinPool {
+                slippage_tolerance: Some(Decimal::percent(1)),
+                receiver: None,
+            },
+            &[coin(1_000_000, "uosmo"), coin(1_000_000, "uwhale")],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .check_address_balance(lp_provider.clone(), lp_denom.clone(), |balance| {
+            assert_eq!(balance, Uint128::new(1_000_000));
+        })
+        .query_osmosis_pool_interface(
+            QueryMsg::SimulateExitPool {
+                shares_in: Uint128::new(500_000),
+            },
+            |result: Result<SimulateExitPoolResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap(),
+                    SimulateExitPoolResponse {
+                        tokens_out: vec![coin(500_000, "uosmo"), coin(500_000, "uwhale")],
+                    }
+                );
+            },
+        )
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::ExitPool { receiver: None },
+            &[coin(500_000, "uwhale")],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("The token sent is not the LP token of the pool"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::ExitPool { receiver: None },
+            &[coin(500_000, lp_denom.clone())],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .check_address_balance(lp_provider.clone(), lp_denom.clone(), |balance| {
+            assert_eq!(balance, Uint128::new(500_000));
+        })
+        .check_address_balance(lp_provider.clone(), "uwhale".to_string(), |balance| {
+            assert_eq!(balance, Uint128::new(1_000_000_000_000_000 - 500_000));
+        })
+        // single sided join, half of the tokens are swapped for the other asset
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::JoinPool {
+                slippage_tolerance: Some(Decimal::percent(5)),
+                receiver: None,
+            },
+            &[coin(100_000, "uwhale")],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .check_address_balance(lp_provider, lp_denom, |balance| {
+            assert!(balance > Uint128::new(500_000));
+        });
+}
+
+#[test]
+fn swap_routes() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    // the router is only stored, so any address works
+    let router = suite.accounts[&1].address();
+    let operations = vec![
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uusdc".to_string(),
+            },
+        },
+    ];
+
+    suite
+        .create_cosmwasm_pool()
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetSwapRoute {
+                offer_denom: "uosmo".to_string(),
+                ask_denom: "uusdc".to_string(),
+                operations: Some(operations.clone()),
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("The router is not set"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::UpdateConfig {
+                white_whale_pool: None,
+                router: Some(router.clone()),
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetConfig {},
+            |result: Result<Config, RunnerError>| {
+                assert_eq!(
+                    result.unwrap().router,
+                    Some(Addr::unchecked(router.clone()))
+                );
+            },
+        )
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::SetSwapRoute {
+                offer_denom: "uosmo".to_string(),
+                ask_denom: "uusdc".to_string(),
+                operations: Some(operations.clone()),
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Unauthorized"));
+            },
+        )
+        // the operations must end with the ask asset
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetSwapRoute {
+                offer_denom: "uosmo".to_string(),
+                ask_denom: "uatom".to_string(),
+                operations: Some(operations.clone()),
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("Invalid swap route from uosmo to uatom"));
+            },
+        )
+        // the operations must be chained
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetSwapRoute {
+                offer_denom: "uosmo".to_string(),
+                ask_denom: "uusdc".to_string(),
+                operations: Some(vec![operations[1].clone()]),
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("Invalid swap route from uosmo to uusdc"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetSwapRoute {
+                offer_denom: "uosmo".to_string(),
+                ask_denom: "uusdc".to_string(),
+                operations: Some(operations.clone()),
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetSwapRoutes {},
+            |result: Result<SwapRoutesResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap(),
+                    SwapRoutesResponse {
+                        swap_routes: vec![SwapRoute {
+                            offer_denom: "uosmo".to_string(),
+                            ask_denom: "uusdc".to_string(),
+                            operations: operations.clone(),
+                        }],
+                    }
+                );
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::CalcInAmtGivenOut {
+                token_out: coin(1_000, "uusdc"),
+                token_in_denom: "uosmo".to_string(),
+                swap_fee: Decimal::permille(3),
+            },
+            |result: Result<CalcInAmtGivenOutResponse, RunnerError>| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("Routed swaps only support swapping an exact amount in"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetSwapRoute {
+                offer_denom: "uosmo".to_string(),
+                ask_denom: "uusdc".to_string(),
+                operations: None,
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetSwapRoutes {},
+            |result: Result<SwapRoutesResponse, RunnerError>| {
+                assert!(result.unwrap().swap_routes.is_empty());
+            },
+        );
+}
+
+#[test]
+fn swap_through_the_pool_twice_in_one_transaction() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    suite
+        .provide_liquidity([
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uosmo".to_string(),
+                },
+                amount: Uint128::new(10_000_000),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uwhale".to_string(),
+                },
+                amount: Uint128::new(10_000_000),
+            },
+        ])
+        .create_cosmwasm_pool();
+
+    let new_account = suite
+        .app
+        .init_account(&[coin(10_000_000_000, "uosmo")])
+        .unwrap();
+
+    // each hop asserts its own minimum receive amount, so the second swap must not overwrite the
+    // assertion data of the first one
+    suite
+        .swap_token_in_through_pools(
+            &new_account,
+            coin(10_000, "uosmo"),
+            vec!["uwhale".to_string(), "uosmo".to_string()],
+            Uint128::new(9_900),
+            |result| {
+                let response = result.unwrap();
+                let token_out_amount = response.data.token_out_amount.parse::<u128>().unwrap();
+                assert!(token_out_amount >= 9_900 && token_out_amount < 10_000);
+            },
+        )
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                result.unwrap();
+            },
+        );
+}
+
+#[test]
+fn bridge_fee() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    suite.provide_liquidity([
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+    ]);
+
+    let new_account = suite
+        .app
+        .init_account(&[coin(10_000_000_000, "uosmo")])
+        .unwrap();
+    let fee_recipient = suite
+        .app
+        .init_account(&[coin(10_000_000_000, "uosmo")])
+        .unwrap();
+
+    suite
+        .create_cosmwasm_pool()
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::SetBridgeFee {
+                share: Decimal::percent(1),
+                recipient: fee_recipient.address(),
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Unauthorized"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetBridgeFee {
+                share: Decimal::percent(11),
+                recipient: fee_recipient.address(),
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("Invalid bridge fee 0.11, it can't be higher than 0.1"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetBridgeFee {
+                share: Decimal::percent(1),
+                recipient: fee_recipient.address(),
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetSwapFee {},
+            |result: Result<GetSwapFeeResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap().swap_fee,
+                    Decimal::permille(3) + Decimal::percent(1)
+                );
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::CalcOutAmtGivenIn {
+                token_in: coin(10_000, "uosmo"),
+                token_out_denom: "uwhale".to_string(),
+                swap_fee: Decimal::permille(3) + Decimal::percent(1),
+            },
+            |result: Result<CalcOutAmtGivenInResponse, RunnerError>| {
+                // 9_963 uwhale out of the pool, minus a 1% bridge fee of 99 uwhale
+                assert_eq!(result.unwrap().token_out, coin(9_864, "uwhale"));
+            },
+        )
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains(
+                    "SwapExactAmountIn returned less than expected. Minimum receive amount: \
+                    9900, received amount after swap: 9864"
+                ));
+            },
+        )
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_800),
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(
+                    response.data,
+                    MsgSwapExactAmountInResponse {
+                        token_out_amount: "9864".to_string()
+                    }
+                );
+            },
+        )
+        .check_address_balance(new_account.address(), "uwhale".into(), |amount| {
+            assert_eq!(amount, Uint128::new(9_864));
+        })
+        .check_address_balance(fee_recipient.address(), "uwhale".into(), |amount| {
+            assert_eq!(amount, Uint128::new(99));
+        })
+        .query_osmosis_pool_interface(
+            QueryMsg::GetBridgeFee {},
+            |result: Result<BridgeFeeResponse, RunnerError>| {
+                let response = result.unwrap();
+                assert_eq!(
+                    response,
+                    BridgeFeeResponse {
+                        bridge_fee: Some(BridgeFee {
+                            share: Decimal::percent(1),
+                            recipient: Addr::unchecked(fee_recipient.address()),
+                        }),
+                        exemptions: vec![],
+                        accrued_fees: vec![coin(99, "uwhale")],
+                    }
+                );
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetBridgeFeeExemption {
+                sender: new_account.address(),
+                exempt: true,
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetBridgeFee {},
+            |result: Result<BridgeFeeResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap().exemptions,
+                    vec![Addr::unchecked(new_account.address())]
+                );
+            },
+        )
+        // exempted senders get the whole amount swapped
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                result.unwrap();
+            },
+        )
+        .check_address_balance(fee_recipient.address(), "uwhale".into(), |amount| {
+            assert_eq!(amount, Uint128::new(99));
+        })
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetBridgeFee {
+                share: Decimal::zero(),
+                recipient: fee_recipient.address(),
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetSwapFee {},
+            |result: Result<GetSwapFeeResponse, RunnerError>| {
+                assert_eq!(result.unwrap().swap_fee, Decimal::permille(1));
+            },
+        );
+}
+
+#[test]
+fn circuit_breaker() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    suite.provide_liquidity([
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+    ]);
+
+    let new_account = suite
+        .app
+        .init_account(&[coin(10_000_000_000, "uosmo")])
+        .unwrap();
+
+    let circuit_breaker_config = CircuitBreakerConfig {
+        max_swap_price_change: Decimal::percent(1),
+        max_block_price_change: Decimal::percent(2),
+    };
+
+    suite
+        .create_cosmwasm_pool()
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::UpdateCircuitBreakerConfig {
+                config: Some(circuit_breaker_config.clone()),
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Unauthorized"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::UpdateCircuitBreakerConfig {
+                config: Some(CircuitBreakerConfig {
+                    max_swap_price_change: Decimal::zero(),
+                    max_block_price_change: Decimal::percent(2),
+                }),
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("The circuit breaker price changes must be positive"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::UpdateCircuitBreakerConfig {
+                config: Some(circuit_breaker_config.clone()),
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        // swapping 1% of the reserves moves the price by about 2%
+        .swap_token_in(
+            &new_account,
+            coin(100_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(90_000),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("The swap moves the spot price by"));
+            },
+        )
+        .check_address_balance(new_account.address(), "uwhale".into(), |amount| {
+            assert_eq!(amount, Uint128::zero());
+        })
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_950),
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetCircuitBreaker {},
+            |result: Result<CircuitBreakerResponse, RunnerError>| {
+                let response = result.unwrap();
+                assert_eq!(response.config, Some(circuit_breaker_config.clone()));
+                assert_eq!(response.price_observations.len(), 1);
+
+                let price_observation = &response.price_observations[0];
+                assert_eq!(price_observation.base_denom, "uosmo");
+                assert_eq!(price_observation.quote_denom, "uwhale");
+                assert_eq!(
+                    price_observation.observation.block_start_price,
+                    Decimal256::one()
+                );
+                assert!(price_observation.observation.last_price < Decimal256::one());
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::UpdateCircuitBreakerConfig { config: None },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .swap_token_in(
+            &new_account,
+            coin(100_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(90_000),
+            |result| {
+                result.unwrap();
+            },
+        );
+}
+
+#[test]
+fn twap() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    suite.provide_liquidity([
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+    ]);
+
+    let new_account = suite
+        .app
+        .init_account(&[
+            coin(10_000_000_000, "uosmo"),
+            coin(10_000_000_000, "uwhale"),
+        ])
+        .unwrap();
+
+    let before_swaps = suite.app.get_block_timestamp();
+    suite.create_cosmwasm_pool().swap_token_in(
+        &new_account,
+        coin(100_000, "uosmo"),
+        "uwhale".to_string(),
+        Uint128::new(90_000),
+        |result| {
+            result.unwrap();
+        },
+    );
+    let after_first_swap = suite.app.get_block_timestamp();
+
+    // the price the first swap leaves is the TWAP until the second one
+    let spot_prices = Rc::new(RefCell::new(vec![]));
+    for (quote_asset_denom, base_asset_denom) in [("uwhale", "uosmo"), ("uosmo", "uwhale")] {
+        let spot_prices = spot_prices.clone();
+        suite.query_osmosis_pool_interface(
+            QueryMsg::PreciseSpotPrice {
+                quote_asset_denom: quote_asset_denom.to_string(),
+                base_asset_denom: base_asset_denom.to_string(),
+            },
+            move |result: Result<PreciseSpotPriceResponse, RunnerError>| {
+                spot_prices.borrow_mut().push(result.unwrap().spot_price);
+            },
+        );
+    }
+
+    suite.app.increase_time(100);
+    suite
+        .swap_token_in(
+            &new_account,
+            coin(100_000, "uwhale"),
+            "uosmo".to_string(),
+            Uint128::new(90_000),
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::Twap {
+                quote_asset_denom: "uwhale".to_string(),
+                base_asset_denom: "uosmo".to_string(),
+                start_time: before_swaps,
+                end_time: Some(after_first_swap.plus_seconds(50)),
+            },
+            |result: Result<TwapResponse, RunnerError>| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("No price of uosmo in uwhale recorded before"));
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::Twap {
+                quote_asset_denom: "uwhale".to_string(),
+                base_asset_denom: "uosmo".to_string(),
+                start_time: after_first_swap.plus_seconds(50),
+                end_time: Some(after_first_swap),
+            },
+            |result: Result<TwapResponse, RunnerError>| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Invalid TWAP period"));
+            },
+        );
+
+    for (i, (quote_asset_denom, base_asset_denom)) in [("uwhale", "uosmo"), ("uosmo", "uwhale")]
+        .into_iter()
+        .enumerate()
+    {
+        let spot_price = spot_prices.borrow()[i];
+        suite.query_osmosis_pool_interface(
+            QueryMsg::Twap {
+                quote_asset_denom: quote_asset_denom.to_string(),
+                base_asset_denom: base_asset_denom.to_string(),
+                start_time: after_first_swap,
+                end_time: Some(after_first_swap.plus_seconds(50)),
+            },
+            move |result: Result<TwapResponse, RunnerError>| {
+                assert_eq!(result.unwrap().twap, spot_price);
+            },
+        );
+    }
+
+    // the second swap moves the price back, so the TWAP up to now sits in between
+    suite.app.increase_time(100);
+    suite.query_osmosis_pool_interface(
+        QueryMsg::Twap {
+            quote_asset_denom: "uwhale".to_string(),
+            base_asset_denom: "uosmo".to_string(),
+            start_time: after_first_swap,
+            end_time: None,
+        },
+        |result: Result<TwapResponse, RunnerError>| {
+            let twap = result.unwrap().twap;
+            assert!(twap > spot_prices.borrow()[0] && twap < Decimal256::one());
+        },
+    );
+
+    // the records older than the max TWAP period are pruned, the TWAP can't start before it
+    suite.app.increase_time(2 * 86_400);
+    let after_max_period = suite.app.get_block_timestamp();
+    suite
+        .swap_token_in(
+            &new_account,
+            coin(100_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(90_000),
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::Twap {
+                quote_asset_denom: "uwhale".to_string(),
+                base_asset_denom: "uosmo".to_string(),
+                start_time: after_first_swap,
+                end_time: None,
+            },
+            |result: Result<TwapResponse, RunnerError>| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Invalid TWAP period"));
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::Twap {
+                quote_asset_denom: "uwhale".to_string(),
+                base_asset_denom: "uosmo".to_string(),
+                start_time: after_max_period.minus_seconds(86_400),
+                end_time: Some(after_max_period),
+            },
+            |result: Result<TwapResponse, RunnerError>| {
+                result.unwrap();
+            },
+        );
+}
+
+#[test]
+fn rate_limits() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    suite.provide_liquidity([
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+    ]);
+
+    let alice = suite
+        .app
+        .init_account(&[coin(10_000_000_000, "uosmo")])
+        .unwrap();
+    let bob = suite
+        .app
+        .init_account(&[coin(10_000_000_000, "uosmo")])
+        .unwrap();
+
+    // the window is long enough not to roll over during the test
+    let rate_limit = RateLimit {
+        window: Duration::Height(1_000_000),
+        max_volume: Uint128::new(25_000),
+        max_sender_volume: Some(Uint128::new(15_000)),
+    };
+
+    suite
+        .create_cosmwasm_pool()
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::SetRateLimit {
+                denom: "uwhale".to_string(),
+                rate_limit: Some(rate_limit.clone()),
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Unauthorized"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetRateLimit {
+                denom: "uwhale".to_string(),
+                rate_limit: Some(RateLimit {
+                    window: Duration::Height(0),
+                    ..rate_limit.clone()
+                }),
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("The rate limit window and volumes must be positive"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetRateLimit {
+                denom: "uwhale".to_string(),
+                rate_limit: Some(rate_limit.clone()),
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .swap_token_in(
+            &alice,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_950),
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetRateLimits {
+                sender: Some(alice.address()),
+            },
+            |result: Result<RateLimitsResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap(),
+                    RateLimitsResponse {
+                        rate_limits: vec![RateLimitResponse {
+                            denom: "uwhale".to_string(),
+                            rate_limit: rate_limit.clone(),
+                            remaining_volume: Uint128::new(15_037),
+                            remaining_sender_volume: Some(Uint128::new(5_037)),
+                        }],
+                    }
+                );
+            },
+        )
+        .swap_token_in(
+            &alice,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains(&format!(
+                    "exceeds the rate limit of 15000 for {}",
+                    alice.address()
+                )));
+            },
+        )
+        // the rate limit of other senders is untouched, while the overall one is shared
+        .swap_token_in(
+            &bob,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                result.unwrap();
+            },
+        )
+        .swap_token_in(
+            &bob,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("in the current window exceeds the rate limit of 25000:"));
+            },
+        )
+        // swaps out of other denoms aren't capped
+        .swap_token_in(
+            &alice,
+            coin(1_000, "uwhale"),
+            "uosmo".to_string(),
+            Uint128::new(990),
+            |result| {
+                result.unwrap();
+            },
+        )
+        // the swap of a single sided join counts against the rate limit too
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::JoinPool {
+                slippage_tolerance: Some(Decimal::percent(5)),
+                receiver: None,
+            },
+            &[coin(20_000, "uosmo")],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("in the current window exceeds the rate limit of 25000:"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetRateLimit {
+                denom: "uwhale".to_string(),
+                rate_limit: None,
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .swap_token_in(
+            &bob,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetRateLimits { sender: None },
+            |result: Result<RateLimitsResponse, RunnerError>| {
+                assert!(result.unwrap().rate_limits.is_empty());
+            },
+        );
+}
+
+#[test]
+fn swap_stats() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    suite.provide_liquidity([
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+    ]);
+
+    let new_account = suite
+        .app
+        .init_account(&[coin(10_000_000_000, "uosmo")])
+        .unwrap();
+
+    suite
+        .create_cosmwasm_pool()
+        .query_osmosis_pool_interface(
+            QueryMsg::Stats {},
+            |result: Result<StatsResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap(),
+                    StatsResponse {
+                        swap_count: 0,
+                        volumes: vec![],
+                    }
+                );
+            },
+        )
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_950),
+            |result| {
+                result.unwrap();
+            },
+        );
+
+    let uosmo_volume = SwapVolume {
+        volume_in: Uint128::new(10_000),
+        volume_out: Uint128::zero(),
+        fees: Uint128::zero(),
+    };
+    // 9_990 uwhale out of the pool minus 9 uwhale for each of the swap, protocol and osmosis fees
+    let uwhale_volume = SwapVolume {
+        volume_in: Uint128::zero(),
+        volume_out: Uint128::new(9_963),
+        fees: Uint128::new(27),
+    };
+
+    let now = suite.app.get_block_timestamp();
+    suite
+        .query_osmosis_pool_interface(
+            QueryMsg::Stats {},
+            |result: Result<StatsResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap(),
+                    StatsResponse {
+                        swap_count: 1,
+                        volumes: vec![
+                            DenomSwapVolume {
+                                denom: "uosmo".to_string(),
+                                volume: uosmo_volume.clone(),
+                            },
+                            DenomSwapVolume {
+                                denom: "uwhale".to_string(),
+                                volume: uwhale_volume.clone(),
+                            },
+                        ],
+                    }
+                );
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::VolumeHistory {
+                denom: "uwhale".to_string(),
+                from: now.minus_seconds(86_400 * 7),
+                to: now,
+            },
+            |result: Result<VolumeHistoryResponse, RunnerError>| {
+                let response = result.unwrap();
+                assert_eq!(response.history.len(), 1);
+                assert_eq!(response.history[0].volume, uwhale_volume);
+                assert!(response.history[0].day_start <= now);
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::VolumeHistory {
+                denom: "uwhale".to_string(),
+                from: now,
+                to: now.minus_seconds(86_400),
+            },
+            |result: Result<VolumeHistoryResponse, RunnerError>| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Invalid volume history period"));
+            },
+        )
+        // the swap of a single sided join is counted like any other, half of the tokens are
+        // swapped for 10_009 uosmo minus 10 uosmo for each of the swap, protocol and osmosis fees
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::JoinPool {
+                slippage_tolerance: Some(Decimal::percent(5)),
+                receiver: None,
+            },
+            &[coin(20_000, "uwhale")],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::Stats {},
+            |result: Result<StatsResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap(),
+                    StatsResponse {
+                        swap_count: 2,
+                        volumes: vec![
+                            DenomSwapVolume {
+                                denom: "uosmo".to_string(),
+                                volume: SwapVolume {
+                                    volume_in: Uint128::new(10_000),
+                                    volume_out: Uint128::new(9_979),
+                                    fees: Uint128::new(30),
+                                },
+                            },
+                            DenomSwapVolume {
+                                denom: "uwhale".to_string(),
+                                volume: SwapVolume {
+                                    volume_in: Uint128::new(10_000),
+                                    volume_out: Uint128::new(9_963),
+                                    fees: Uint128::new(27),
+                                },
+                            },
+                        ],
+                    }
+                );
+            },
+        );
+}
+
+#[test]
+fn swap_events() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    suite.provide_liquidity([
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+    ]);
+
+    let new_account = suite
+        .app
+        .init_account(&[coin(10_000_000_000, "uosmo")])
+        .unwrap();
+    let sender = new_account.address();
+
+    suite
+        .create_cosmwasm_pool()
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_950),
+            |result| {
+                let events = result.unwrap().events;
+
+                let swap = wasm_event_attributes(&events, "swap");
+                let reply_id = swap["reply_id"].clone();
+                assert_eq!(swap["swap_type"], "exact_amount_in");
+                assert_eq!(swap["sender"], sender);
+                assert_eq!(swap["token_in_denom"], "uosmo");
+                assert_eq!(swap["token_in_amount"], "10000");
+                assert_eq!(swap["token_in_max_amount"], "10000");
+                assert_eq!(swap["token_out_denom"], "uwhale");
+                assert_eq!(swap["token_out_amount"], "9963");
+                assert_eq!(swap["token_out_min_amount"], "9950");
+                assert_eq!(swap["swap_fee"], "0.001");
+                assert_eq!(swap["routed"], "false");
+
+                let settlement = wasm_event_attributes(&events, "swap_settlement");
+                assert_eq!(settlement["swap_type"], "exact_amount_in");
+                assert_eq!(settlement["reply_id"], reply_id);
+                assert_eq!(settlement["recipient"], sender);
+                assert_eq!(settlement["token_out_denom"], "uwhale");
+                assert_eq!(settlement["token_out_min_amount"], "9950");
+                assert_eq!(settlement["token_out_amount"], "9963");
+                assert_eq!(settlement["bridge_fee_amount"], "0");
+                assert_eq!(settlement["sent_amount"], "9963");
+            },
+        )
+        .swap_token_out(
+            &new_account,
+            coin(10_000, "uwhale"),
+            "uosmo".to_string(),
+            Uint128::new(10_100),
+            |result| {
+                let response = result.unwrap();
+
+                let swap = wasm_event_attributes(&response.events, "swap");
+                assert_eq!(swap["swap_type"], "exact_amount_out");
+                assert_eq!(swap["token_in_denom"], "uosmo");
+                assert_eq!(swap["token_in_amount"], response.data.token_in_amount);
+                assert_eq!(swap["token_in_max_amount"], "10100");
+                assert_eq!(swap["token_out_amount"], "10000");
+                assert_eq!(swap["token_out_min_amount"], "10000");
+
+                let settlement = wasm_event_attributes(&response.events, "swap_settlement");
+                assert_eq!(settlement["swap_type"], "exact_amount_out");
+                assert_eq!(settlement["reply_id"], swap["reply_id"]);
+                assert_eq!(settlement["token_out_min_amount"], "10000");
+                assert_eq!(settlement["sent_amount"], "10000");
+            },
+        );
+}
+
+#[test]
+fn gauges() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    let gauge = MsgCreateGauge {
+        is_perpetual: false,
+        owner: String::new(),
+        distribute_to: Some(QueryCondition {
+            lock_query_type: LockQueryType::ByDuration as i32,
+            denom: format!("factory/{}/uLP", suite.ww_pool_addr),
+            duration: Some(osmosis_std::shim::Duration {
+                seconds: 86_400,
+                nanos: 0,
+            }),
+            timestamp: None,
+        }),
+        coins: vec![coin(1_000_000, "uwhale").into()],
+        start_time: None,
+        num_epochs_paid_over: 1,
+        pool_id: 0,
+    };
+
+    let gauge_id = Rc::new(RefCell::new(String::new()));
+
+    suite
+        .create_cosmwasm_pool_with_gauges(
+            Some(CreatePoolGauges::DefaultLockableDurations {}),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains(
+                    "Invalid gauge: the default lockable durations gauges aren't supported"
+                ));
+            },
+        )
+        .create_cosmwasm_pool();
+
+    // the contract holds swapped tokens in flight and bridge fees, which never pay for the gauges
+    let contract = suite.cw_osmosis_pool_interface.clone();
+    suite
+        .send_tokens(0, contract.clone(), &[coin(1_000_000, "uwhale")])
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::CreateGauges {
+                msgs: vec![gauge.clone()],
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Unauthorized"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::CreateGauges {
+                msgs: vec![MsgCreateGauge {
+                    coins: vec![],
+                    ..gauge.clone()
+                }],
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("Invalid gauge: the gauge coins can't be empty"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::CreateGauges {
+                msgs: vec![MsgCreateGauge {
+                    distribute_to: None,
+                    ..gauge.clone()
+                }],
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("Invalid gauge: the gauge must have a distribution condition"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::CreateGauges {
+                msgs: vec![MsgCreateGauge {
+                    is_perpetual: true,
+                    num_epochs_paid_over: 7,
+                    ..gauge.clone()
+                }],
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("Invalid gauge: perpetual gauges must be paid over a single epoch"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::CreateGauges {
+                msgs: vec![gauge.clone()],
+            },
+            &[coin(50_000_000, "uosmo")],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains(
+                    "Invalid gauge: the funds sent [50000000uosmo] must match the gauge coins and \
+                    fees [50000000uosmo,1000000uwhale]"
+                ));
+            },
+        )
+        // the funds sent pay for the gauge coins and the creation fee
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::CreateGauges {
+                msgs: vec![gauge.clone()],
+            },
+            &[coin(50_000_000, "uosmo"), coin(1_000_000, "uwhale")],
+            |result| {
+                let events = result.unwrap().events;
+                let create_gauge = events
+                    .iter()
+                    .find(|event| event.ty == "create_gauge")
+                    .expect("No create_gauge event emitted");
+                *gauge_id.borrow_mut() = create_gauge
+                    .attributes
+                    .iter()
+                    .find(|attribute| attribute.key == "gauge_id")
+                    .unwrap()
+                    .value
+                    .clone();
+            },
+        );
+
+    suite.check_address_balance(contract.clone(), "uwhale".to_string(), |amount| {
+        assert_eq!(amount, Uint128::new(1_000_000));
+    });
+
+    let gauge_id = gauge_id.borrow().parse::<u64>().unwrap();
+    suite
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::AddToGauge {
+                gauge_id,
+                rewards: vec![coin(0, "uwhale")],
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("Invalid gauge: the rewards must be positive"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::AddToGauge {
+                gauge_id,
+                rewards: vec![coin(500_000, "uwhale")],
+            },
+            &[coin(25_000_000, "uosmo")],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("must match the gauge coins and fees [25000000uosmo,500000uwhale]"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::AddToGauge {
+                gauge_id,
+                rewards: vec![coin(500_000, "uwhale")],
+            },
+            &[coin(25_000_000, "uosmo"), coin(500_000, "uwhale")],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .check_address_balance(contract, "uwhale".to_string(), |amount| {
+            assert_eq!(amount, Uint128::new(1_000_000));
+        });
+}
+
+#[test]
+fn sender_access_list() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    suite.provide_liquidity([
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+    ]);
+
+    let alice = suite
+        .app
+        .init_account(&[coin(10_000_000_000, "uosmo")])
+        .unwrap();
+    let bob = suite
+        .app
+        .init_account(&[coin(10_000_000_000, "uosmo")])
+        .unwrap();
+
+    suite
+        .create_cosmwasm_pool()
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::SetSenderAccessMode {
+                mode: Some(SenderAccessMode::Denylist),
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Unauthorized"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetSenderAccessMode {
+                mode: Some(SenderAccessMode::Denylist),
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::UpdateSenderAccessList {
+                add: vec![alice.address()],
+                remove: vec![],
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        // alice is denied in both swap directions, while bob can swap
+        .swap_token_in(
+            &alice,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_950),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains(&format!(
+                    "The sender {} is not allowed to swap",
+                    alice.address()
+                )));
+            },
+        )
+        .swap_token_out(
+            &alice,
+            coin(10_000, "uwhale"),
+            "uosmo".to_string(),
+            Uint128::new(10_100),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("is not allowed to swap"));
+            },
+        )
+        .swap_token_in(
+            &bob,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_950),
+            |result| {
+                result.unwrap();
+            },
+        )
+        // only alice is allowed once the list becomes an allowlist
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetSenderAccessMode {
+                mode: Some(SenderAccessMode::Allowlist),
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .swap_token_in(
+            &alice,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                result.unwrap();
+            },
+        )
+        .swap_token_out(
+            &bob,
+            coin(10_000, "uwhale"),
+            "uosmo".to_string(),
+            Uint128::new(10_100),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains(&format!(
+                    "The sender {} is not allowed to swap",
+                    bob.address()
+                )));
+            },
+        )
+        // the swap of a single sided join is subject to the list too
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::JoinPool {
+                slippage_tolerance: Some(Decimal::percent(5)),
+                receiver: None,
+            },
+            &[coin(20_000, "uosmo")],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("is not allowed to swap"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::UpdateSenderAccessList {
+                add: vec![bob.address()],
+                remove: vec![],
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .swap_token_out(
+            &bob,
+            coin(10_000, "uwhale"),
+            "uosmo".to_string(),
+            Uint128::new(10_100),
+            |result| {
+                result.unwrap();
+            },
+        );
+
+    let mut senders = vec![
+        Addr::unchecked(alice.address()),
+        Addr::unchecked(bob.address()),
+    ];
+    senders.sort();
+
+    suite
+        .query_osmosis_pool_interface(
+            QueryMsg::GetSenderAccessList {
+                start_after: None,
+                limit: Some(1),
+            },
+            |result: Result<SenderAccessListResponse, RunnerError>| {
+                let response = result.unwrap();
+                assert_eq!(response.mode, Some(SenderAccessMode::Allowlist));
+                assert_eq!(response.senders, vec![senders[0].clone()]);
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetSenderAccessList {
+                start_after: Some(senders[0].to_string()),
+                limit: None,
+            },
+            |result: Result<SenderAccessListResponse, RunnerError>| {
+                assert_eq!(result.unwrap().senders, vec![senders[1].clone()]);
+            },
+        )
+        // removing the mode lets anyone swap again
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::UpdateSenderAccessList {
+                add: vec![],
+                remove: vec![alice.address(), bob.address()],
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetSenderAccessMode { mode: None },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .swap_token_in(
+            &alice,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetSenderAccessList {
+                start_after: None,
+                limit: None,
+            },
+            |result: Result<SenderAccessListResponse, RunnerError>| {
+                let response = result.unwrap();
+                assert_eq!(response.mode, None);
+                assert!(response.senders.is_empty());
+            },
+        );
+}
+
+#[test]
+fn denom_pause() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    suite.provide_liquidity([
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+    ]);
+
+    let new_account = suite
+        .app
+        .init_account(&[
+            coin(10_000_000_000, "uosmo"),
+            coin(10_000_000_000, "uwhale"),
+        ])
+        .unwrap();
+
+    // uwhale is depegging, so selling it is paused
+    let pause = DenomPause {
+        sell: true,
+        buy: false,
+    };
+
+    suite
+        .create_cosmwasm_pool()
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::SetDenomPause {
+                denom: "uwhale".to_string(),
+                pause: pause.clone(),
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("Unauthorized"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetDenomPause {
+                denom: "uwhale".to_string(),
+                pause: pause.clone(),
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uwhale"),
+            "uosmo".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("Swapping uwhale for uosmo is paused"));
+            },
+        )
+        .swap_token_out(
+            &new_account,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(10_100),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("Swapping uwhale for uosmo is paused"));
+            },
+        )
+        // a single sided join with uwhale swaps it for uosmo, so it's paused too
+        .execute_osmosis_pool_interface(
+            1,
+            ExecuteMsg::JoinPool {
+                slippage_tolerance: Some(Decimal::percent(5)),
+                receiver: None,
+            },
+            &[coin(20_000, "uwhale")],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("Swapping uwhale for uosmo is paused"));
+            },
+        )
+        // buying uwhale is still enabled
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetSwapDirections {},
+            |result: Result<SwapDirectionsResponse, RunnerError>| {
+                let response = result.unwrap();
+                assert!(response.is_active);
+                assert_eq!(
+                    response.directions,
+                    vec![
+                        SwapDirection {
+                            token_in_denom: "uosmo".to_string(),
+                            token_out_denom: "uwhale".to_string(),
+                            is_enabled: true,
+                        },
+                        SwapDirection {
+                            token_in_denom: "uwhale".to_string(),
+                            token_out_denom: "uosmo".to_string(),
+                            is_enabled: false,
+                        },
+                    ]
+                );
+                assert_eq!(
+                    response.paused_denoms,
+                    vec![PausedDenom {
+                        denom: "uwhale".to_string(),
+                        pause: pause.clone(),
+                    }]
+                );
+            },
+        )
+        // pausing buying uosmo pauses the same direction
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetDenomPause {
+                denom: "uwhale".to_string(),
+                pause: DenomPause::default(),
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetDenomPause {
+                denom: "uosmo".to_string(),
+                pause: DenomPause {
+                    sell: false,
+                    buy: true,
+                },
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uwhale"),
+            "uosmo".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err
+                    .to_string()
+                    .contains("Swapping uwhale for uosmo is paused"));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetDenomPause {
+                denom: "uosmo".to_string(),
+                pause: DenomPause::default(),
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uwhale"),
+            "uosmo".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                result.unwrap();
+            },
+        )
+        // deactivating the pool disables every direction
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetActive {
+                is_active: false,
+                from: None,
+                until: None,
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetSwapDirections {},
+            |result: Result<SwapDirectionsResponse, RunnerError>| {
+                let response = result.unwrap();
+                assert!(!response.is_active);
+                assert!(response
+                    .directions
+                    .iter()
+                    .all(|direction| !direction.is_enabled));
+                assert!(response.paused_denoms.is_empty());
+            },
+        );
+}
+
+#[test]
+fn scheduled_activation() {
+    let mut suite = TestingSuite::default_with_balances(&[
+        coin(1_000_000_000_000_000, "uosmo"),
+        coin(1_000_000_000_000_000, "uwhale"),
+    ]);
+
+    suite.create_ww_pool(
+        [
+            AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+        ],
+        [6, 6],
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            osmosis_fee: Fee {
+                share: Decimal::permille(1),
+            },
+        },
+    );
+
+    suite.provide_liquidity([
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uwhale".to_string(),
+            },
+            amount: Uint128::new(10_000_000),
+        },
+    ]);
+
+    let new_account = suite
+        .app
+        .init_account(&[coin(10_000_000_000, "uosmo")])
+        .unwrap();
+
+    suite.create_cosmwasm_pool();
+
+    let now = suite.app.get_block_timestamp();
+    let pause_end = now.plus_seconds(86_400);
+
+    suite
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetActive {
+                is_active: false,
+                from: None,
+                until: Some(now.minus_seconds(1)),
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains(
+                    "The active window must end after it starts and after the current block time"
+                ));
+            },
+        )
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetActive {
+                is_active: false,
+                from: Some(pause_end),
+                until: Some(now.plus_seconds(3_600)),
+            },
+            &[],
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("The active window must end"));
+            },
+        )
+        // pause the pool for a day after an incident
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetActive {
+                is_active: false,
+                from: None,
+                until: Some(pause_end),
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("The pool is currently inactive"));
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::GetActiveStatus {},
+            |result: Result<ActiveStatusResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap(),
+                    ActiveStatusResponse {
+                        is_active: false,
+                        status: false,
+                        active_window: Some(ActiveWindow {
+                            from: None,
+                            until: Some(pause_end),
+                        }),
+                    }
+                );
+            },
+        );
+
+    suite.app.increase_time(86_400);
+
+    suite
+        .query_osmosis_pool_interface(
+            QueryMsg::IsActive {},
+            |result: Result<IsActiveResponse, RunnerError>| {
+                assert_eq!(result.unwrap(), IsActiveResponse { is_active: true });
+            },
+        )
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                result.unwrap();
+            },
+        );
+
+    // go live at a launch time an hour from now
+    let launch = suite.app.get_block_timestamp().plus_seconds(3_600);
+
+    suite
+        .execute_osmosis_pool_interface(
+            0,
+            ExecuteMsg::SetActive {
+                is_active: true,
+                from: Some(launch),
+                until: None,
+            },
+            &[],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_osmosis_pool_interface(
+            QueryMsg::IsActive {},
+            |result: Result<IsActiveResponse, RunnerError>| {
+                assert_eq!(result.unwrap(), IsActiveResponse { is_active: false });
+            },
+        )
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("The pool is currently inactive"));
+            },
+        );
+
+    suite.app.increase_time(3_600);
+
+    suite
+        .swap_token_in(
+            &new_account,
+            coin(10_000, "uosmo"),
+            "uwhale".to_string(),
+            Uint128::new(9_900),
+            |result| {
+                result.unwrap();
+            },
+        )
+        // setting the status without a window applies it indefinitely
+        .set_active(false, |result| {
+            result.unwrap();
+        })
+        .query_osmosis_pool_interface(
+            QueryMsg::GetActiveStatus {},
+            |result: Result<ActiveStatusResponse, RunnerError>| {
+                assert_eq!(
+                    result.unwrap(),
+                    ActiveStatusResponse {
+                        is_active: false,
+                        status: false,
+                        active_window: None,
+                    }
+                );
+            },
+        );
+}
+
+/// Gets the attributes of the wasm event of the given type emitted by the contract
+fn wasm_event_attributes(events: &[Event], ty: &str) -> HashMap<String, String> {
+    events
+        .iter()
+        .find(|event| event.ty == format!("wasm-{}", ty))
+        .unwrap_or_else(|| panic!("No {} event emitted", ty))
+        .attributes
+        .iter()
+        .map(|attribute| (attribute.key.clone(), attribute.value.clone()))
+        .collect()
+}
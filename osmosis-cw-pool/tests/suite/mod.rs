@@ -1,10 +1,11 @@
 use std::collections::HashMap;
 
-use cosmwasm_std::{to_json_binary, Coin, Uint128};
-use osmosis_std::types::cosmos::bank::v1beta1::QueryBalanceRequest;
+use cosmwasm_std::{to_json_binary, Coin, Decimal, Uint128};
+use osmosis_std::types::cosmos::bank::v1beta1::{MsgSend, QueryBalanceRequest};
+use osmosis_std::types::cosmwasm::wasm::v1::MsgExecuteContractResponse;
 use osmosis_std::types::osmosis::cosmwasmpool::v1beta1::{
     ContractInfoByPoolIdRequest, ContractInfoByPoolIdResponse, MsgCreateCosmWasmPool,
-    UploadCosmWasmPoolCodeAndWhiteListProposal,
+    MsgCreateCosmWasmPoolResponse, UploadCosmWasmPoolCodeAndWhiteListProposal,
 };
 use osmosis_std::types::osmosis::poolmanager::v1beta1::{
     MsgSwapExactAmountIn, MsgSwapExactAmountInResponse, MsgSwapExactAmountOut,
@@ -17,7 +18,9 @@ use osmosis_test_tube::{
 use white_whale_std::pool_network::asset::{Asset, AssetInfo, PairType, ToCoins};
 use white_whale_std::pool_network::pair::PoolFee;
 
-use osmosis_cw_pool::msg::{InstantiateMsg, QueryMsg, SudoMsg};
+use osmosis_cw_pool::msg::{
+    AfterPoolCreated, CreatePoolGauges, ExecuteMsg, InstantiateMsg, QueryMsg, SudoMsg,
+};
 
 use crate::osmosis_cosmwasm_pool::CosmwasmPool;
 pub struct TestingSuite {
@@ -55,6 +58,22 @@ impl TestingSuite {
         asset_infos: [AssetInfo; 2],
         asset_decimals: [u8; 2],
         pool_fees: PoolFee,
+    ) -> &mut Self {
+        self.create_ww_pool_with_type(
+            asset_infos,
+            asset_decimals,
+            pool_fees,
+            PairType::ConstantProduct,
+        )
+    }
+
+    #[track_caller]
+    pub fn create_ww_pool_with_type(
+        &mut self,
+        asset_infos: [AssetInfo; 2],
+        asset_decimals: [u8; 2],
+        pool_fees: PoolFee,
+        pair_type: PairType,
     ) -> &mut Self {
         let wasm = Wasm::new(&self.app);
         let admin = &self.accounts[&0];
@@ -69,7 +88,7 @@ impl TestingSuite {
                     asset_decimals,
                     pool_fees,
                     fee_collector_addr: admin.address(),
-                    pair_type: PairType::ConstantProduct,
+                    pair_type,
                     token_factory_lp: true,
                 },
                 None,
@@ -103,6 +122,24 @@ impl TestingSuite {
 
         self
     }
+
+    #[track_caller]
+    pub fn send_tokens(&mut self, sender: usize, recipient: String, coins: &[Coin]) -> &mut Self {
+        let sender = &self.accounts[&sender];
+
+        Bank::new(&self.app)
+            .send(
+                MsgSend {
+                    from_address: sender.address(),
+                    to_address: recipient,
+                    amount: coins.iter().cloned().map(Into::into).collect(),
+                },
+                sender,
+            )
+            .unwrap();
+
+        self
+    }
 }
 
 /// pool related actions
@@ -181,6 +218,39 @@ impl TestingSuite {
 
         self
     }
+    #[track_caller]
+    pub fn swap_token_in_through_pools(
+        &mut self,
+        sender: &SigningAccount,
+        token_in: Coin,
+        token_out_denoms: Vec<String>,
+        token_out_min_amount: Uint128,
+        result: impl Fn(RunnerExecuteResult<MsgSwapExactAmountInResponse>),
+    ) -> &mut Self {
+        let cp = CosmwasmPool::new(&self.app);
+
+        // every hop goes through the cosmwasm pool
+        let routes = token_out_denoms
+            .into_iter()
+            .map(|token_out_denom| SwapAmountInRoute {
+                pool_id: self.osmosis_pool_id,
+                token_out_denom,
+            })
+            .collect();
+
+        result(cp.swap_exact_amount_in(
+            MsgSwapExactAmountIn {
+                sender: sender.address(),
+                token_in: Some(token_in.into()),
+                routes,
+                token_out_min_amount: token_out_min_amount.into(),
+            },
+            sender,
+        ));
+
+        self
+    }
+
     #[track_caller]
     pub fn swap_token_out(
         &mut self,
@@ -219,7 +289,100 @@ impl TestingSuite {
         result(execute_sudo(
             &self.app,
             &self.cw_osmosis_pool_interface,
-            SudoMsg::SetActive { is_active: active },
+            SudoMsg::SetActive {
+                is_active: active,
+                from: None,
+                until: None,
+            },
+        ));
+        self
+    }
+
+    #[track_caller]
+    pub fn update_slippage_config(
+        &mut self,
+        max_spread: Option<Decimal>,
+        use_belief_price: Option<bool>,
+        result: impl Fn(Result<Vec<u8>, RunnerError>),
+    ) -> &mut Self {
+        result(execute_sudo(
+            &self.app,
+            &self.cw_osmosis_pool_interface,
+            SudoMsg::UpdateSlippageConfig {
+                max_spread,
+                use_belief_price,
+            },
+        ));
+        self
+    }
+
+    #[track_caller]
+    pub fn update_spot_price_config(
+        &mut self,
+        include_fees: bool,
+        result: impl Fn(Result<Vec<u8>, RunnerError>),
+    ) -> &mut Self {
+        result(execute_sudo(
+            &self.app,
+            &self.cw_osmosis_pool_interface,
+            SudoMsg::UpdateSpotPriceConfig { include_fees },
+        ));
+        self
+    }
+
+    #[track_caller]
+    pub fn register_cw20(
+        &mut self,
+        contract_addr: String,
+        subdenom: String,
+        result: impl Fn(Result<Vec<u8>, RunnerError>),
+    ) -> &mut Self {
+        result(execute_sudo(
+            &self.app,
+            &self.cw_osmosis_pool_interface,
+            SudoMsg::RegisterCw20 {
+                contract_addr,
+                subdenom,
+            },
+        ));
+        self
+    }
+
+    #[track_caller]
+    pub fn set_max_spread_override(
+        &mut self,
+        offer_denom: String,
+        ask_denom: String,
+        max_spread: Option<Decimal>,
+        result: impl Fn(Result<Vec<u8>, RunnerError>),
+    ) -> &mut Self {
+        result(execute_sudo(
+            &self.app,
+            &self.cw_osmosis_pool_interface,
+            SudoMsg::SetMaxSpreadOverride {
+                offer_denom,
+                ask_denom,
+                max_spread,
+            },
+        ));
+        self
+    }
+
+    #[track_caller]
+    pub fn execute_osmosis_pool_interface(
+        &mut self,
+        sender: usize,
+        msg: ExecuteMsg,
+        funds: &[Coin],
+        result: impl Fn(RunnerExecuteResult<MsgExecuteContractResponse>),
+    ) -> &mut Self {
+        let wasm = Wasm::new(&self.app);
+
+        result(wasm.execute(
+            &self.cw_osmosis_pool_interface,
+            &msg,
+            funds,
+            &self.accounts[&sender],
         ));
         self
     }
@@ -249,6 +412,17 @@ impl TestingSuite {
 impl TestingSuite {
     #[track_caller]
     pub fn create_cosmwasm_pool(&mut self) -> &mut Self {
+        self.create_cosmwasm_pool_with_gauges(None, |result| {
+            result.unwrap();
+        })
+    }
+
+    #[track_caller]
+    pub fn create_cosmwasm_pool_with_gauges(
+        &mut self,
+        create_pool_gauges: Option<CreatePoolGauges>,
+        result: impl Fn(RunnerExecuteResult<MsgCreateCosmWasmPoolResponse>),
+    ) -> &mut Self {
         let cp = CosmwasmPool::new(&self.app);
         let gov = GovWithAppAccess::new(&self.app);
 
@@ -268,22 +442,31 @@ impl TestingSuite {
         .unwrap();
 
         let instantiate_msg = &InstantiateMsg {
+            owner: Some(signer.address()),
             white_whale_pool: self.ww_pool_addr.clone(),
-            after_pool_created: None,
+            after_pool_created: create_pool_gauges.map(|create_pool_gauges| AfterPoolCreated {
+                create_pool_guages: Some(create_pool_gauges),
+            }),
+            slippage_config: None,
+            spot_price_config: None,
         };
 
-        let res = cp
-            .create_cosmwasm_pool(
-                MsgCreateCosmWasmPool {
-                    code_id,
-                    instantiate_msg: to_json_binary(instantiate_msg).unwrap().to_vec(),
-                    sender: signer.address(),
-                },
-                signer,
-            )
-            .unwrap();
+        let res = cp.create_cosmwasm_pool(
+            MsgCreateCosmWasmPool {
+                code_id,
+                instantiate_msg: to_json_binary(instantiate_msg).unwrap().to_vec(),
+                sender: signer.address(),
+            },
+            signer,
+        );
+
+        let pool_id = res.as_ref().ok().map(|res| res.data.pool_id);
+        result(res);
 
-        let pool_id = res.data.pool_id;
+        let pool_id = match pool_id {
+            Some(pool_id) => pool_id,
+            None => return self,
+        };
 
         let ContractInfoByPoolIdResponse {
             contract_address,